@@ -1,30 +1,544 @@
+use std::fs::read_to_string;
+use std::path::Path;
 use std::str::FromStr;
 
 use regex::Regex;
+use serde::Deserialize;
 // --------------------------------------------------------------------------- //
 /// Une dimension en 2d, c'est un tuple avec deux grandeurs.
 // --------------------------------------------------------------------------- //
 #[derive(Clone, Copy)]
 pub struct Dimension(u32, u32);
 impl Dimension {
+    /// Creates a dimension of the given width and height, both of which must
+    /// be positive.
+    pub fn new(width: u32, height: u32) -> Result<Dimension, &'static str> {
+        if width == 0 || height == 0 {
+            Err("The width and the height must be positive")
+        } else {
+            Ok(Dimension(width, height))
+        }
+    }
     pub fn x(self) -> u32 { self.0 }
     pub fn y(self) -> u32 { self.1 }
+    /// The largest square fitting in this dimension
+    pub fn square(self) -> Dimension {
+        let side = self.0.min(self.1);
+        Dimension(side, side)
+    }
 }
 
-static DIM_FMT: &str = r"(?P<WIDTH>\d+),\s*(?P<HEIGHT>\d+)";
+/// The width used when only the height is given
+pub const DEFAULT_WIDTH : u32 = 80;
+/// The height used when only the width is given
+pub const DEFAULT_HEIGHT: u32 = 24;
+
+impl Default for Dimension {
+    /// The size of a standard terminal
+    fn default() -> Self {
+        Dimension(DEFAULT_WIDTH, DEFAULT_HEIGHT)
+    }
+}
+
+static DIM_FMT: &str = r"^\s*(?P<WIDTH>\d+)?\s*(?:,\s*(?P<HEIGHT>\d+))?\s*$";
 lazy_static! {
     static ref DIM_RE : Regex = Regex::new(DIM_FMT).unwrap();
 }
 
 impl FromStr for Dimension {
     type Err = &'static str;
+    /// Parses either `width,height`, `width` alone or `,height` alone. The
+    /// missing dimension takes its default value.
     fn from_str(txt: &str) -> Result<Dimension, Self::Err> {
-        if let Some(caps) = DIM_RE.captures(txt) {
-            let w = caps["WIDTH"].parse::<u32>().unwrap();
-            let h = caps["HEIGHT"].parse::<u32>().unwrap();
-            Ok(Dimension(w, h))
-        } else {
-            Err("Input does not conform to format 'width,height'")
+        const FORMAT: &str = "Input does not conform to format 'width,height', 'width' or ',height'";
+
+        let caps = DIM_RE.captures(txt).ok_or(FORMAT)?;
+        let w    = caps.name("WIDTH") .map(|w| w.as_str().parse::<u32>().map_err(|_| FORMAT)).transpose()?;
+        let h    = caps.name("HEIGHT").map(|h| h.as_str().parse::<u32>().map_err(|_| FORMAT)).transpose()?;
+        if w.is_none() && h.is_none() {
+            return Err(FORMAT);
+        }
+        Dimension::new(w.unwrap_or(DEFAULT_WIDTH), h.unwrap_or(DEFAULT_HEIGHT))
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The formats in which the result may be printed on the standard output
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// The summary statistics of each trace, in json
+    JsonStats,
+    /// The summary statistics of each trace, in yaml
+    Yaml,
+    /// The graph as a png image encoded in a base64 data uri
+    PngBase64,
+    /// The lines of the traces, in csv
+    Csv,
+    /// The solution of each trace, as a json array (see `--print-solution`)
+    Json
+}
+
+impl FromStr for Format {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<Format, Self::Err> {
+        match txt {
+            "json-stats" => Ok(Format::JsonStats),
+            "yaml"       => Ok(Format::Yaml),
+            "png-base64" => Ok(Format::PngBase64),
+            "csv"        => Ok(Format::Csv),
+            "json"       => Ok(Format::Json),
+            _            => Err("Unknown format (expected 'json-stats', 'yaml', 'png-base64', 'csv' or 'json')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The series of bounds which can be plotted
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Lower,
+    Upper
+}
+
+impl FromStr for Bound {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<Bound, Self::Err> {
+        match txt {
+            "lb" => Ok(Bound::Lower),
+            "ub" => Ok(Bound::Upper),
+            _    => Err("Unknown bound (expected 'lb' or 'ub')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The kinds of log lines
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    /// The lines reporting the progress of the search
+    Ongoing,
+    /// The lines reporting the optimum
+    Final
+}
+
+impl FromStr for LineKind {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<LineKind, Self::Err> {
+        match txt {
+            "ongoing" => Ok(LineKind::Ongoing),
+            "final"   => Ok(LineKind::Final),
+            _         => Err("Unknown kind of line (expected 'ongoing' or 'final')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The series which can be drawn for each trace
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Series {
+    LowerBound,
+    UpperBound,
+    Fringe,
+    Gap,
+    RelativeGap
+}
+
+impl FromStr for Series {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<Series, Self::Err> {
+        match txt {
+            "lb"     => Ok(Series::LowerBound),
+            "ub"     => Ok(Series::UpperBound),
+            "fringe" => Ok(Series::Fringe),
+            "gap"    => Ok(Series::Gap),
+            "rgap"   => Ok(Series::RelativeGap),
+            _        => Err("Unknown series (expected 'lb', 'ub', 'fringe', 'gap' or 'rgap')")
         }
     }
+}
+
+// --------------------------------------------------------------------------- //
+/// A color explicitly assigned to one series of one trace. It is written as
+/// `<trace>.<series>=<#RRGGBB>`, for instance `baseline.ub=#FF0000`.
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SeriesColor {
+    pub trace : String,
+    pub series: Series,
+    pub color : String
+}
+
+static COLOR_FMT: &str = r"^#[0-9A-Fa-f]{6}$";
+lazy_static! {
+    static ref COLOR_RE : Regex = Regex::new(COLOR_FMT).unwrap();
+}
+
+impl FromStr for SeriesColor {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<SeriesColor, Self::Err> {
+        const FORMAT: &str = "Input does not conform to format '<trace>.<series>=<#RRGGBB>'";
+
+        let (key,   color ) = txt.rsplit_once('=').ok_or(FORMAT)?;
+        let (trace, series) = key.rsplit_once('.').ok_or(FORMAT)?;
+        if trace.is_empty() || !COLOR_RE.is_match(color) {
+            return Err(FORMAT);
+        }
+        Ok(SeriesColor {
+            trace : trace.to_string(),
+            series: series.parse()?,
+            color : color.to_string()
+        })
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// How the gap between the bounds is to be plotted
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GapMode {
+    /// The difference between the upper and lower bounds
+    Absolute,
+    /// The difference between the bounds relative to the upper bound
+    Relative,
+    /// Both the absolute and relative gaps, side by side
+    Both
+}
+
+impl FromStr for GapMode {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<GapMode, Self::Err> {
+        match txt {
+            "absolute" => Ok(GapMode::Absolute),
+            "relative" => Ok(GapMode::Relative),
+            "both"     => Ok(GapMode::Both),
+            _          => Err("Unknown gap mode (expected 'absolute', 'relative' or 'both')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// How several traces (e.g. repeated runs of the same instance) are
+/// aggregated into one series
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregate {
+    /// The band between the smallest and largest upper bounds, along with
+    /// their median
+    MinMax
+}
+
+impl FromStr for Aggregate {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<Aggregate, Self::Err> {
+        match txt {
+            "min-max" => Ok(Aggregate::MinMax),
+            _         => Err("Unknown aggregate (expected 'min-max')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The final number of each trace which is compared by the bar chart
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FinalMetric {
+    /// The optimum reported by the `Final` line
+    Value,
+    /// The gap between the bounds at the last line
+    Gap
+}
+
+impl FromStr for FinalMetric {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<FinalMetric, Self::Err> {
+        match txt {
+            "value" => Ok(FinalMetric::Value),
+            "gap"   => Ok(FinalMetric::Gap),
+            _       => Err("Unknown final metric (expected 'value' or 'gap')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The way the final value of each trace is drawn on the bounds view
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FinalStyle {
+    /// A dashed line spanning the whole x-axis
+    Line,
+    /// A point at the explored count of the `Final` line
+    Point,
+    /// A point along with a short horizontal whisker
+    Whisker
+}
+
+impl FromStr for FinalStyle {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<FinalStyle, Self::Err> {
+        match txt {
+            "line"    => Ok(FinalStyle::Line),
+            "point"   => Ok(FinalStyle::Point),
+            "whisker" => Ok(FinalStyle::Whisker),
+            _         => Err("Unknown final style (expected 'line', 'point' or 'whisker')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The quantity of the log lines which goes on the x-axis of the bounds view
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XField {
+    /// The number of explored nodes (the usual view of the search progress)
+    Explored,
+    /// The fringe size, which gives a phase-space view of the bounds
+    Fringe,
+    /// The ordinal of the line in the trace (1, 2, 3, ...), for the logs whose
+    /// explored counts are missing or unreliable
+    Index,
+    /// The number of seconds elapsed when the line was logged (for the
+    /// timestamped traces)
+    Time
+}
+
+impl XField {
+    /// The label of an axis showing this quantity
+    pub fn label(self) -> &'static str {
+        match self {
+            XField::Explored => "Explored Nodes",
+            XField::Fringe   => "Frontier Size",
+            XField::Index    => "Iteration",
+            XField::Time     => "Time (s)"
+        }
+    }
+}
+
+impl FromStr for XField {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<XField, Self::Err> {
+        match txt {
+            "explored" => Ok(XField::Explored),
+            "fringe"   => Ok(XField::Fringe),
+            "index"    => Ok(XField::Index),
+            "time"     => Ok(XField::Time),
+            _          => Err("Unknown x-field (expected 'explored', 'fringe', 'index' or 'time')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The axes along which the grid lines are drawn
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridLines {
+    /// No grid at all (the default)
+    Off,
+    /// Vertical lines at the ticks of the x-axis
+    X,
+    /// Horizontal lines at the ticks of the y-axis
+    Y,
+    /// Lines along both axes
+    Both
+}
+
+impl FromStr for GridLines {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<GridLines, Self::Err> {
+        match txt {
+            "none" => Ok(GridLines::Off),
+            "x"    => Ok(GridLines::X),
+            "y"    => Ok(GridLines::Y),
+            "both" => Ok(GridLines::Both),
+            _      => Err("Unknown grid (expected 'none', 'x', 'y' or 'both')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The base of the logarithm used to plot a series in log scale
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogBase {
+    E,
+    Two,
+    Ten
+}
+
+impl LogBase {
+    /// The logarithm of x in this base
+    pub fn log(self, x: f64) -> f64 {
+        match self {
+            LogBase::E   => x.ln(),
+            LogBase::Two => x.log2(),
+            LogBase::Ten => x.log10()
+        }
+    }
+    /// The label of an axis giving the logarithm of the given quantity
+    pub fn label(self, quantity: &str) -> String {
+        match self {
+            LogBase::E   => format!("ln({})", quantity),
+            LogBase::Two => format!("log2({})", quantity),
+            LogBase::Ten => format!("log10({})", quantity)
+        }
+    }
+}
+
+impl FromStr for LogBase {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<LogBase, Self::Err> {
+        match txt {
+            "e"  => Ok(LogBase::E),
+            "2"  => Ok(LogBase::Two),
+            "10" => Ok(LogBase::Ten),
+            _    => Err("Unknown log base (expected 'e', '2' or '10')")
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// The default options read from a toml configuration file. Any option which
+/// is explicitly given on the command line takes precedence over these.
+///
+/// Example:
+/// ```toml
+/// palette     = ["#FF0000", "#00FF00", "#0000FF"]
+/// dimension   = "120,40"
+/// x-label     = "Explored Nodes"
+/// y-label     = "Objective"
+/// marker-size = 2.0
+/// rich-legend = true
+/// ```
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Defaults {
+    pub palette    : Option<Vec<String>>,
+    pub dimension  : Option<String>,
+    pub x_label    : Option<String>,
+    pub y_label    : Option<String>,
+    pub marker_size: Option<f32>,
+    pub max_files  : Option<usize>,
+    pub rich_legend: Option<bool>,
+    pub segments   : Option<bool>,
+    pub x_from_zero: Option<bool>,
+}
+
+/// The name of the configuration file which is looked up in the current
+/// directory when none is given explicitly.
+pub const DEFAULT_CONFIG_FILE: &str = ".ddo-plotter.toml";
+
+impl Defaults {
+    /// Reads the default options from the given toml file
+    pub fn load(path: &Path) -> Result<Defaults, String> {
+        let text     = read_to_string(path).map_err(|e| e.to_string())?;
+        let defaults = toml::from_str::<Defaults>(&text).map_err(|e| e.to_string())?;
+
+        if let Some(dim) = &defaults.dimension {
+            Dimension::from_str(dim)?;
+        }
+        if defaults.palette.as_ref().is_some_and(Vec::is_empty) {
+            return Err("The palette must contain at least one color".to_string());
+        }
+        if defaults.marker_size.is_some_and(|size| size <= 0.0) {
+            return Err("The marker size must be positive".to_string());
+        }
+        Ok(defaults)
+    }
+    /// The default dimension (if any)
+    pub fn dimension(&self) -> Option<Dimension> {
+        self.dimension.as_ref().and_then(|dim| Dimension::from_str(dim).ok())
+    }
+    /// Resolves a switch: the state given on the command line (if any) takes
+    /// precedence over the default one, and the switch is off when neither is
+    /// given
+    pub fn switch(cli: Option<bool>, default: Option<bool>) -> bool {
+        cli.or(default).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config::{Defaults, Dimension, Series, SeriesColor, DEFAULT_HEIGHT, DEFAULT_WIDTH};
+
+    #[test]
+    fn parse_series_color() {
+        let parsed = "a.ub=#FF0000".parse::<SeriesColor>().unwrap();
+        assert_eq!("a",                parsed.trace);
+        assert_eq!(Series::UpperBound, parsed.series);
+        assert_eq!("#FF0000",          parsed.color);
+
+        let dotted = "run.1.fringe=#00ff00".parse::<SeriesColor>().unwrap();
+        assert_eq!("run.1",            dotted.trace);
+        assert_eq!(Series::Fringe,     dotted.series);
+    }
+    #[test]
+    fn parse_series_color_fails() {
+        assert!("a.ub".parse::<SeriesColor>().is_err());
+        assert!("ub=#FF0000".parse::<SeriesColor>().is_err());
+        assert!(".ub=#FF0000".parse::<SeriesColor>().is_err());
+        assert!("a.xx=#FF0000".parse::<SeriesColor>().is_err());
+        assert!("a.ub=red".parse::<SeriesColor>().is_err());
+    }
+    #[test]
+    fn parse_full_dimension() {
+        let dim = "120,24".parse::<Dimension>().unwrap();
+        assert_eq!((120, 24), (dim.x(), dim.y()));
+
+        let dim = "120, 24".parse::<Dimension>().unwrap();
+        assert_eq!((120, 24), (dim.x(), dim.y()));
+    }
+    #[test]
+    fn parse_width_only() {
+        let dim = "120".parse::<Dimension>().unwrap();
+        assert_eq!((120, DEFAULT_HEIGHT), (dim.x(), dim.y()));
+    }
+    #[test]
+    fn parse_height_only() {
+        let dim = ",24".parse::<Dimension>().unwrap();
+        assert_eq!((DEFAULT_WIDTH, 24), (dim.x(), dim.y()));
+    }
+    #[test]
+    fn parse_dimension_fails() {
+        assert!("".parse::<Dimension>().is_err());
+        assert!(",".parse::<Dimension>().is_err());
+        assert!("120,".parse::<Dimension>().is_err());
+        assert!("wide".parse::<Dimension>().is_err());
+        assert!("99999999999,24".parse::<Dimension>().is_err());
+        assert!("0,24".parse::<Dimension>().is_err());
+    }
+    #[test]
+    fn new_dimension_must_be_positive() {
+        let dim = Dimension::new(120, 24).unwrap();
+        assert_eq!((120, 24), (dim.x(), dim.y()));
+
+        assert!(Dimension::new(0, 24).is_err());
+        assert!(Dimension::new(120, 0).is_err());
+        assert!(Dimension::new(0, 0).is_err());
+    }
+    #[test]
+    fn square_dimension() {
+        let dim = "300,200".parse::<Dimension>().unwrap().square();
+        assert_eq!((200, 200), (dim.x(), dim.y()));
+    }
+    #[test]
+    fn default_dimension() {
+        let dim = Dimension::default();
+        assert_eq!((80, 24), (dim.x(), dim.y()));
+    }
+    #[test]
+    fn command_line_switches_override_the_defaults() {
+        let path = std::env::temp_dir().join("ddo-plotter-config-test.toml");
+        std::fs::write(&path, "rich-legend = true\n").unwrap();
+        let defaults = Defaults::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(Defaults::switch(None, defaults.rich_legend));
+        assert!(!Defaults::switch(Some(false), defaults.rich_legend));
+        assert!(Defaults::switch(Some(true), None));
+        assert!(!Defaults::switch(None, defaults.segments));
+    }
 }
\ No newline at end of file
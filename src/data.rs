@@ -1,27 +1,42 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::convert::TryFrom;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Lines};
+use std::fmt;
+use std::fs::{read_to_string, File};
+use std::io::{BufRead, BufReader, ErrorKind, Lines};
 use std::path::Path;
+use std::str::FromStr;
 
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 // --------------------------------------------------------------------------- //
 /// A log line outputed by the ddo library solver can have either of the
 /// following two formats:
 /// *  `Explored 6700, LB 11, UB 12, Fringe sz 90`
 /// *  `Final 11, Explored 6790`
+///
+/// Each line also remembers the segment of the trace it belongs to. Segments
+/// are delimited by restart markers (`--- restart 3 ---`) in the trace.
+///
+/// Optionally, a line can be prefixed by a timestamp giving the number of
+/// seconds elapsed since the beginning of the run (`[12.345s] Final 11, ...`).
 // --------------------------------------------------------------------------- //
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum LogLine {
     Ongoing {
         explored: usize,
         lb      : i32,
         ub      : i32,
-        fringe  : usize
+        fringe  : usize,
+        segment : usize,
+        time    : Option<f64>
     },
     Final {
         explored : usize,
-        opt_value: i32
+        opt_value: i32,
+        segment  : usize,
+        time     : Option<f64>
     }
 }
 
@@ -50,96 +65,1104 @@ impl LogLine {
             LogLine::Final   { .. }           => 0
         }
     }
+    /// True iff the lower bound is a sentinel (e.g. the `-inf`-like initial
+    /// lower bound of a maximization) rather than an actual bound
+    pub fn has_sentinel_lb(&self) -> bool {
+        is_sentinel(self.lb())
+    }
+    /// True iff the upper bound is a sentinel (e.g. the `+inf`-like initial
+    /// upper bound) rather than an actual bound
+    pub fn has_sentinel_ub(&self) -> bool {
+        is_sentinel(self.ub())
+    }
+    /// True iff this is the `Final` line reporting the optimum
+    pub fn is_final(&self) -> bool {
+        matches!(self, LogLine::Final { .. })
+    }
+    /// True iff this line reports the progress of an ongoing search
+    pub fn is_ongoing(&self) -> bool {
+        matches!(self, LogLine::Ongoing { .. })
+    }
+    pub fn segment(&self) -> usize {
+        match self {
+            LogLine::Ongoing {segment, .. }   => *segment,
+            LogLine::Final   {segment, .. }   => *segment
+        }
+    }
+    /// The number of seconds elapsed when this line was logged (if known)
+    pub fn time(&self) -> Option<f64> {
+        match self {
+            LogLine::Ongoing {time, .. }      => *time,
+            LogLine::Final   {time, .. }      => *time
+        }
+    }
+    /// Returns a copy of this line, tagged as belonging to the given segment
+    pub fn in_segment(self, s: usize) -> LogLine {
+        match self {
+            LogLine::Ongoing {explored, lb, ub, fringe, time, ..} =>
+                LogLine::Ongoing {explored, lb, ub, fringe, segment: s, time},
+            LogLine::Final   {explored, opt_value, time, ..} =>
+                LogLine::Final   {explored, opt_value, segment: s, time}
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+// Affichage d'une logline (au format canonique de ddo)
+// --------------------------------------------------------------------------- //
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(time) = self.time() {
+            write!(f, "[{}s] ", time)?;
+        }
+        match self {
+            LogLine::Ongoing {explored, lb, ub, fringe, ..} =>
+                write!(f, "Explored {}, LB {}, UB {}, Fringe sz {}", explored, lb, ub, fringe),
+            LogLine::Final   {explored, opt_value, ..} =>
+                write!(f, "Final {}, Explored {}", opt_value, explored)
+        }
+    }
+}
+
+// --------------------------------------------------------------------------- //
+/// A column of the csv export of the traces, extracted from each log line
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsvColumn {
+    Explored,
+    Lb,
+    Ub,
+    Fringe,
+    /// The gap between the bounds (`ub - lb`)
+    Gap,
+    /// The number of seconds elapsed when the line was logged (empty if unknown)
+    Time
+}
+
+/// The columns of the csv export unless specified otherwise
+pub const DEFAULT_CSV_COLUMNS: [CsvColumn; 4] = [CsvColumn::Explored, CsvColumn::Lb, CsvColumn::Ub, CsvColumn::Fringe];
+
+impl CsvColumn {
+    /// The name of the column (as given in its header)
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvColumn::Explored => "explored",
+            CsvColumn::Lb       => "lb",
+            CsvColumn::Ub       => "ub",
+            CsvColumn::Fringe   => "fringe",
+            CsvColumn::Gap      => "gap",
+            CsvColumn::Time     => "time"
+        }
+    }
+    /// The value of the column for the given line
+    pub fn value(self, ll: &LogLine) -> String {
+        match self {
+            CsvColumn::Explored => ll.explored().to_string(),
+            CsvColumn::Lb       => ll.lb().to_string(),
+            CsvColumn::Ub       => ll.ub().to_string(),
+            CsvColumn::Fringe   => ll.fringe().to_string(),
+            CsvColumn::Gap      => (ll.ub() as i64 - ll.lb() as i64).to_string(),
+            CsvColumn::Time     => ll.time().map_or(String::new(), |t| t.to_string())
+        }
+    }
+    /// The columns given as a comma separated list of names (e.g. `explored,gap,ub`)
+    pub fn parse_list(txt: &str) -> Result<Vec<CsvColumn>, &'static str> {
+        txt.split(',').map(|name| name.trim().parse()).collect()
+    }
+}
+
+impl FromStr for CsvColumn {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<CsvColumn, Self::Err> {
+        match txt {
+            "explored" => Ok(CsvColumn::Explored),
+            "lb"       => Ok(CsvColumn::Lb),
+            "ub"       => Ok(CsvColumn::Ub),
+            "fringe"   => Ok(CsvColumn::Fringe),
+            "gap"      => Ok(CsvColumn::Gap),
+            "time"     => Ok(CsvColumn::Time),
+            _          => Err("Unknown csv column (expected 'explored', 'lb', 'ub', 'fringe', 'gap' or 'time')")
+        }
+    }
 }
 
 // --------------------------------------------------------------------------- //
 // Parsing d'une logline
 // --------------------------------------------------------------------------- //
+// The counts may be written with thousands separators (e.g. `Explored 6,700`)
 static ONGOING_FMT : &str =
-    r"Explored (?P<explored>\d+), LB (?P<lb>-?\d+), UB (?P<ub>-?\d+), Fringe sz (?P<fringe>\d+)";
+    r"Explored (?P<explored>\d{1,3}(?:,\d{3})+|\d+), LB (?P<lb>-?\d+), UB (?P<ub>-?\d+), Fringe sz (?P<fringe>\d{1,3}(?:,\d{3})+|\d+)";
 static FINAL_FMT : &str =
-    r"Final (?P<opt>-?\d+), Explored (?P<explored>\d+)";
+    r"Final (?P<opt>-?\d+), Explored (?P<explored>\d{1,3}(?:,\d{3})+|\d+)";
+static RESTART_FMT : &str =
+    r"--- restart \d+ ---";
+static TIME_FMT : &str =
+    r"^\s*\[\s*(?P<time>\d+(?:\.\d+)?)s?\]";
+static COMPUTED_IN_FMT : &str =
+    r"computed in\s+(?P<value>\d+(?:\.\d+)?)\s*(?P<unit>ns|µs|us|ms|s)\b";
+static SOLUTION_FMT : &str =
+    r"^\s*### Solution:";
+static ASSIGNMENT_FMT : &str =
+    r"^\s*-?\d+(?:\s+-?\d+)*\s*$";
+static ANSI_FMT : &str =
+    r"\x1b\[[0-9;]*m";
 
 lazy_static! {
-    static ref ONGOING_EXP: Regex = Regex::new(ONGOING_FMT).unwrap();
-    static ref FINAL_EXP  : Regex= Regex::new(FINAL_FMT).unwrap();
+    static ref RESTART_EXP: Regex = Regex::new(RESTART_FMT).unwrap();
+    static ref TIME_EXP   : Regex = Regex::new(TIME_FMT).unwrap();
+    static ref SOLUTION_EXP   : Regex = Regex::new(SOLUTION_FMT).unwrap();
+    static ref ASSIGNMENT_EXP : Regex = Regex::new(ASSIGNMENT_FMT).unwrap();
+    static ref ANSI_EXP   : Regex = Regex::new(ANSI_FMT).unwrap();
 }
 
-impl TryFrom<&str> for LogLine {
-    type Error = ();
+/// Removes the ANSI color codes (`\x1b[...m`) from the line, which are found in
+/// the logs of a solver run with colored output and teed to a file
+pub fn strip_ansi(line: &str) -> Cow<'_, str> {
+    ANSI_EXP.replace_all(line, "")
+}
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        if let Some(captures) = ONGOING_EXP.captures(value) {
-            return Ok(LogLine::Ongoing {
-                explored: captures["explored"].parse::<usize>().unwrap(),
+// --------------------------------------------------------------------------- //
+/// The wording of the log lines written by one version of ddo. It is given by
+/// the regular expressions which recognize the ongoing lines, the `Final` line
+/// and the summary line.
+// --------------------------------------------------------------------------- //
+#[derive(Debug)]
+pub struct LogFormat {
+    /// The version of ddo which writes its logs this way
+    pub version: &'static str,
+    ongoing    : Regex,
+    last       : Regex,
+    summary    : Regex
+}
+
+/// The version whose wording is used unless specified otherwise
+pub const LATEST_VERSION: &str = "latest";
+
+/// The known wordings: `(version, ongoing, final, summary)`. The wording of an
+/// older version of ddo is to be added here as soon as its logs are met.
+static LOG_FORMATS: &[(&str, &str, &str, &str)] = &[
+    (LATEST_VERSION, ONGOING_FMT, FINAL_FMT, COMPUTED_IN_FMT),
+];
+
+lazy_static! {
+    static ref FORMATS: Vec<LogFormat> = LOG_FORMATS.iter()
+        .map(|(version, ongoing, last, summary)| LogFormat {
+            version,
+            ongoing: Regex::new(ongoing).unwrap(),
+            last   : Regex::new(last).unwrap(),
+            summary: Regex::new(summary).unwrap()
+        })
+        .collect();
+}
+
+/// Parses a count which may be written with thousands separators (`1,234`).
+/// Yields None when the count does not fit in an usize.
+fn parse_count(txt: &str) -> Option<usize> {
+    txt.replace(',', "").parse::<usize>().ok()
+}
+
+impl LogFormat {
+    /// The wording of the given version of ddo (if it is known)
+    pub fn of(version: &str) -> Option<&'static LogFormat> {
+        FORMATS.iter().find(|format| format.version == version)
+    }
+    /// The wording of the latest version of ddo
+    pub fn latest() -> &'static LogFormat {
+        LogFormat::of(LATEST_VERSION).unwrap()
+    }
+    /// The versions whose wording is known
+    pub fn versions() -> Vec<&'static str> {
+        FORMATS.iter().map(|format| format.version).collect()
+    }
+    /// Parses one log line written in this wording (if it is a log line)
+    pub fn parse(&self, value: &str) -> Option<LogLine> {
+        // Logs captured on windows or copy-pasted may carry a trailing `\r`
+        // and stray spaces
+        let value = value.trim();
+        let time = TIME_EXP.captures(value)
+            .map(|captures| captures["time"].parse::<f64>().unwrap());
+
+        if let Some(captures) = self.ongoing.captures(value) {
+            return Some(LogLine::Ongoing {
+                explored: parse_count(&captures["explored"])?,
                 lb      : captures["lb"].parse::<i32>().unwrap(),
                 ub      : captures["ub"].parse::<i32>().unwrap(),
-                fringe  : captures["fringe"].parse::<usize>().unwrap(),
+                fringe  : parse_count(&captures["fringe"])?,
+                segment : 0,
+                time
             });
         }
 
-        if let Some(captures) = FINAL_EXP.captures(value) {
-            return Ok(LogLine::Final {
-                explored :  captures["explored"].parse::<usize>().unwrap(),
+        if let Some(captures) = self.last.captures(value) {
+            return Some(LogLine::Final {
+                explored :  parse_count(&captures["explored"])?,
                 opt_value: captures["opt"].parse::<i32>().unwrap(),
+                segment  : 0,
+                time
             });
         }
 
-        Err(())
+        None
+    }
+    /// Parses the duration of a summary line written in this wording (see
+    /// `parse_computed_in`)
+    pub fn parse_computed_in(&self, line: &str) -> Option<f64> {
+        let captures = self.summary.captures(line)?;
+        let value    = captures["value"].parse::<f64>().ok()?;
+        let per_sec  = match &captures["unit"] {
+            "s"         => 1.0,
+            "ms"        => 1e3,
+            "µs" | "us" => 1e6,
+            "ns"        => 1e9,
+            _           => return None
+        };
+        Some(value / per_sec)
+    }
+}
+
+impl TryFrom<&str> for LogLine {
+    type Error = ();
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        LogFormat::latest().parse(value).ok_or(())
     }
 }
 
+/// The (x, y) coordinates of the points of a plotted series
+pub type Points = Vec<(f64, f64)>;
+
 // --------------------------------------------------------------------------- //
 /// Une trace, c'est une collection de log lines ...
 // --------------------------------------------------------------------------- //
 #[derive(Clone, Debug)]
 pub struct Trace {
-    pub name : Option<String>,
-    pub lines: Vec<LogLine>
+    pub name    : Option<String>,
+    pub lines   : Vec<LogLine>,
+    /// The solve time (in seconds) reported by the summary line, if any
+    pub seconds : Option<f64>,
+    /// The assignment reported in the solution block, if any
+    pub solution: Option<Vec<i32>>
 }
 
 impl Trace {
-    pub fn lb_explored(&self) -> Vec<(f64, f64)> {
+    /// The name of this trace (if any)
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    /// Returns this trace renamed after the given name
+    pub fn with_name(self, name: impl Into<String>) -> Self {
+        Trace { name: Some(name.into()), ..self }
+    }
+    /// The points obtained by extracting an x and a y value from each line
+    pub fn series<X, Y>(&self, x: X, y: Y) -> Points
+        where X: Fn(&LogLine) -> f64,
+              Y: Fn(&LogLine) -> f64
+    {
         self.lines.iter()
-            .map(|ll| (ll.explored() as f64, ll.lb() as f64))
+            .map(|ll| (x(ll), y(ll)))
             .collect()
     }
+    pub fn lb_explored(&self) -> Vec<(f64, f64)> {
+        self.series(|ll| ll.explored() as f64, |ll| ll.lb() as f64)
+    }
     pub fn ub_explored(&self) -> Vec<(f64, f64)> {
+        self.series(|ll| ll.explored() as f64, |ll| ll.ub() as f64)
+    }
+    /// The largest fringe size reached so far, at each explored count
+    pub fn fringe_highwater(&self) -> Vec<(f64, f64)> {
+        let mut highwater = 0;
         self.lines.iter()
-            .map(|ll| (ll.explored() as f64, ll.ub() as f64))
+            .map(|ll| {
+                highwater = highwater.max(ll.fringe());
+                (ll.explored() as f64, highwater as f64)
+            })
             .collect()
     }
+    /// The value at which the upper bound is capped when it is clamped to
+    /// `factor` times the final optimum (`opt + (factor - 1) * |opt|`, which
+    /// also holds for negative optima). None when the trace has no `Final` line.
+    pub fn ub_cap(&self, factor: f64) -> Option<f64> {
+        self.final_value()
+            .map(|opt| opt as f64)
+            .map(|opt| opt + (factor - 1.0) * opt.abs())
+    }
+    /// Splits the upper bound series in the points that lie below the cap and
+    /// those which lie above it. The latter are moved down to the cap.
+    pub fn ub_explored_clamped(&self, cap: f64) -> (Points, Points) {
+        clamped(self.ub_explored(), cap)
+    }
     pub fn fringe_explored(&self) -> Vec<(f64, f64)> {
         self.lines.iter()
             .map(|ll| (ll.explored() as f64, ll.fringe() as f64))
             .collect()
     }
+    /// Groups the lines in `bins` bins of equal width spanning the explored
+    /// counts of the trace, and gives the mean explored count and fringe size
+    /// of each bin. The empty bins are left out.
+    pub fn binned_fringe(&self, bins: usize) -> Vec<(f64, f64)> {
+        let explored = self.lines.iter().map(|ll| ll.explored() as f64);
+        let min      = explored.clone().fold(f64::INFINITY, f64::min);
+        let max      = explored.fold(f64::NEG_INFINITY, f64::max);
+        let width    = (max - min) / bins.max(1) as f64;
+
+        let mut sums = vec![(0.0, 0.0, 0usize); bins.max(1)];
+        for ll in self.lines.iter() {
+            let x   = ll.explored() as f64;
+            let bin = if width > 0.0 { (((x - min) / width) as usize).min(sums.len() - 1) } else { 0 };
+            sums[bin].0 += x;
+            sums[bin].1 += ll.fringe() as f64;
+            sums[bin].2 += 1;
+        }
+        sums.into_iter()
+            .filter(|(_, _, n)| *n > 0)
+            .map(|(x, fringe, n)| (x / n as f64, fringe / n as f64))
+            .collect()
+    }
+    /// The size of the fringe relative to the number of explored nodes: how
+    /// much of the search is still pending relative to the work done. The
+    /// lines where no node was explored yet are left out.
+    pub fn fringe_ratio(&self) -> Vec<(f64, f64)> {
+        self.lines.iter()
+            .filter(|ll| ll.explored() > 0)
+            .map(|ll| (ll.explored() as f64, ll.fringe() as f64 / ll.explored() as f64))
+            .collect()
+    }
+    /// For each distinct upper bound value seen in the trace, the first
+    /// explored count at which it was reached (in order of appearance).
+    pub fn ub_milestones(&self) -> Vec<(i32, usize)> {
+        milestones(self.lines.iter().map(|ll| (ll.ub(), ll.explored())))
+    }
+    /// For each distinct lower bound value seen in the trace, the first
+    /// explored count at which it was reached (in order of appearance).
+    pub fn lb_milestones(&self) -> Vec<(i32, usize)> {
+        milestones(self.lines.iter().map(|ll| (ll.lb(), ll.explored())))
+    }
+    /// The assignment reported in the solution block of the trace (if any)
+    pub fn solution(&self) -> Option<&[i32]> {
+        self.solution.as_deref()
+    }
+    /// The explored count at which the first incumbent was found, that is the
+    /// first line where the lower bound improves over the one the search
+    /// started with. None when the lower bound never improves.
+    pub fn first_incumbent(&self) -> Option<usize> {
+        let initial = self.lines.first()?.lb();
+        self.lines.iter().find(|ll| ll.lb() > initial).map(LogLine::explored)
+    }
+    /// The smallest lower bound and the largest upper bound of the trace.
+    /// This is `(0, 0)` when the trace is empty.
+    pub fn bound_range(&self) -> (i32, i32) {
+        let min = self.lines.iter().map(|ll| ll.lb().min(ll.ub())).min();
+        let max = self.lines.iter().map(|ll| ll.lb().max(ll.ub())).max();
+        (min.unwrap_or(0), max.unwrap_or(0))
+    }
+    /// The smallest and largest bounds of the trace which are not sentinels.
+    /// None when the trace has no such bound.
+    pub fn clipped_bound_range(&self) -> Option<(i32, i32)> {
+        let bounds = self.lines.iter()
+            .flat_map(|ll| std::iter::once(ll.lb()).chain(std::iter::once(ll.ub())))
+            .filter(|bound| !is_sentinel(*bound));
+        Some((bounds.clone().min()?, bounds.max()?))
+    }
+    /// The smallest and largest explored counts of the trace (if it is not empty)
+    pub fn explored_range(&self) -> Option<(usize, usize)> {
+        let min = self.lines.iter().map(LogLine::explored).min()?;
+        let max = self.lines.iter().map(LogLine::explored).max()?;
+        Some((min, max))
+    }
+    /// The smallest and largest fringe sizes of the trace. This is `(0, 0)`
+    /// when the trace is empty.
+    pub fn fringe_range(&self) -> (usize, usize) {
+        let min = self.lines.iter().map(LogLine::fringe).min();
+        let max = self.lines.iter().map(LogLine::fringe).max();
+        (min.unwrap_or(0), max.unwrap_or(0))
+    }
+    /// The optimum reported by the `Final` line of the trace (if any)
+    pub fn final_value(&self) -> Option<i32> {
+        self.lines.iter().find(|ll| ll.is_final()).map(LogLine::lb)
+    }
+    /// The solve time in seconds (if known). This is the time reported by the
+    /// summary line or, when there is none, the time at which the last line
+    /// was logged.
+    pub fn duration(&self) -> Option<f64> {
+        self.seconds.or_else(|| self.lines.last().and_then(LogLine::time))
+    }
+    /// The range of y values to use when plotting the fringe size of this
+    /// trace: the fringe range, padded on both sides. (`fringe_range` gives
+    /// the exact sizes.)
+    pub fn padded_fringe_range(&self) -> (f64, f64) {
+        let (min, max) = self.fringe_range();
+        padded(min as f64, max as f64)
+    }
+    /// True iff the search was run to completion (the trace has a `Final`
+    /// line). When this is false, the solver most likely timed out.
+    pub fn is_proven(&self) -> bool {
+        self.lines.iter().any(LogLine::is_final)
+    }
+    /// The upper bound at the given explored count, linearly interpolated
+    /// between the surrounding lines of the trace. Returns None when the
+    /// explored count lies outside of the range covered by the trace.
+    pub fn ub_at(&self, explored: usize) -> Option<f64> {
+        interpolate(&self.ub_explored(), explored as f64)
+    }
+    /// The lower bound at the given explored count, linearly interpolated
+    /// between the surrounding lines of the trace. Returns None when the
+    /// explored count lies outside of the range covered by the trace.
+    pub fn lb_at(&self, explored: usize) -> Option<f64> {
+        interpolate(&self.lb_explored(), explored as f64)
+    }
+    /// The explored count at which the gap first closed to within the given
+    /// tolerance. The gap is linearly interpolated between the lines, so that
+    /// this is where it crossed the tolerance rather than where that crossing
+    /// was logged. None when the gap never closed that much.
+    pub fn explored_to_close(&self, tolerance: f64) -> Option<f64> {
+        let gaps  = self.gap_explored();
+        let index = gaps.iter().position(|(_, gap)| *gap <= tolerance)?;
+        if index == 0 {
+            return Some(gaps[0].0);
+        }
+        let ((x0, g0), (x1, g1)) = (gaps[index - 1], gaps[index]);
+        Some(x0 + (x1 - x0) * (g0 - tolerance) / (g0 - g1))
+    }
+    /// The upper bound of this trace as a ratio to the upper bound of the
+    /// baseline at the same explored count. Since both traces were not logged
+    /// at the same explored counts, the baseline is interpolated at those of
+    /// this trace. The points lying outside of the range covered by the
+    /// baseline (or where its upper bound is null) are dropped.
+    pub fn ub_ratio(&self, baseline: &Trace) -> Points {
+        let base = baseline.ub_explored();
+        self.ub_explored().into_iter()
+            .filter_map(|(x, ub)| interpolate(&base, x)
+                .filter(|b| *b != 0.0)
+                .map(|b| (x, ub / b)))
+            .collect()
+    }
+    /// Returns a copy of this trace which only retains the lines whose explored
+    /// count lies in the (inclusive) window `[from, to]`. A missing bound
+    /// leaves the window open on that side.
+    pub fn window(&self, from: Option<usize>, to: Option<usize>) -> Trace {
+        let from = from.unwrap_or(usize::MIN);
+        let to   = to.unwrap_or(usize::MAX);
+        self.filter(|ll| from <= ll.explored() && ll.explored() <= to)
+    }
+    /// Returns a copy of this trace which only retains the lines logged in the
+    /// (inclusive) time window `[since, until]`, in seconds from the start of
+    /// the search. A missing bound leaves the window open on that side. Lines
+    /// that have no timestamp are dropped.
+    pub fn window_time(&self, since: Option<f64>, until: Option<f64>) -> Trace {
+        let since = since.unwrap_or(f64::NEG_INFINITY);
+        let until = until.unwrap_or(f64::INFINITY);
+        self.filter(|ll| ll.time().is_some_and(|t| since <= t && t <= until))
+    }
+    /// True iff all the lines of this (non empty) trace are timestamped
+    pub fn has_timestamps(&self) -> bool {
+        !self.lines.is_empty() && self.lines.iter().all(|ll| ll.time().is_some())
+    }
+    /// Returns a copy of this trace which only keeps one line per `secs`
+    /// seconds time bucket: the last line logged in that bucket. The buckets
+    /// are counted from the timestamp of the first line. Lines that have no
+    /// timestamp are dropped.
+    pub fn resample_time(&self, secs: f64) -> Trace {
+        let mut lines: Vec<LogLine> = vec![];
+        let mut start = None;
+        let mut last_bucket = None;
+        for line in self.lines.iter() {
+            if let Some(time) = line.time() {
+                let origin = *start.get_or_insert(time);
+                let bucket = ((time - origin) / secs).floor() as i64;
+                if last_bucket == Some(bucket) {
+                    lines.pop();
+                }
+                lines.push(*line);
+                last_bucket = Some(bucket);
+            }
+        }
+        self.with_lines(lines)
+    }
+    /// Returns a copy of this trace which only retains the lines where one of
+    /// the bounds changed with respect to the previous retained line. The first
+    /// and last lines are always retained, so that the curves keep their span.
+    pub fn changes_only(&self) -> Trace {
+        let mut lines: Vec<LogLine> = vec![];
+        for line in self.lines.iter() {
+            match lines.last() {
+                Some(prev) if prev.lb() == line.lb() && prev.ub() == line.ub() => {},
+                _ => lines.push(*line)
+            }
+        }
+        if let (Some(last), Some(kept)) = (self.lines.last(), lines.last()) {
+            if last != kept {
+                lines.push(*last);
+            }
+        }
+        self.with_lines(lines)
+    }
+    /// True iff a `Final` line precedes some ongoing line, as happens in logs
+    /// which were concatenated or reordered
+    pub fn has_out_of_order_final(&self) -> bool {
+        self.lines.iter()
+            .skip_while(|ll| !ll.is_final())
+            .any(LogLine::is_ongoing)
+    }
+    /// Returns a copy of this trace where the `Final` lines are moved after all
+    /// the ongoing lines (whose order is left untouched)
+    pub fn with_final_last(&self) -> Trace {
+        let (ongoing, last): (Vec<LogLine>, Vec<LogLine>) = self.lines.iter().partition(|ll| ll.is_ongoing());
+        self.with_lines(ongoing.into_iter().chain(last).collect())
+    }
+    /// Returns a copy of this trace which only retains the lines satisfying
+    /// the given predicate (e.g. `LogLine::is_ongoing`)
+    pub fn filter(&self, keep: impl Fn(&LogLine) -> bool) -> Trace {
+        self.with_lines(self.lines.iter().filter(|ll| keep(ll)).copied().collect())
+    }
+    /// Returns a copy of this trace without its first `n` lines. This is
+    /// mostly useful to get rid of the warm-up phase whose wild bounds would
+    /// otherwise dominate the auto-scaled y-range.
+    pub fn skip(&self, n: usize) -> Trace {
+        self.with_lines(self.lines.iter().skip(n).copied().collect())
+    }
+    /// Returns a copy of this trace (name, time and solution) made of the
+    /// given lines
+    fn with_lines(&self, lines: Vec<LogLine>) -> Trace {
+        Trace {
+            name    : self.name.clone(),
+            lines,
+            seconds : self.seconds,
+            solution: self.solution.clone()
+        }
+    }
+    pub fn gap_explored(&self) -> Vec<(f64, f64)> {
+        self.lines.iter()
+            .map(|ll| (ll.explored() as f64, ll.ub() as f64 - ll.lb() as f64))
+            .collect()
+    }
+    /// The gap relative to the magnitude of the upper bound, expressed as a
+    /// fraction: `(ub - lb) / max(1, |ub|)`. Using at least 1 as denominator
+    /// guards against the division by zero when the upper bound is null.
+    pub fn relative_gap_explored(&self) -> Vec<(f64, f64)> {
+        self.lines.iter()
+            .map(|ll| {
+                let gap   = ll.ub() as f64 - ll.lb() as f64;
+                let scale = (ll.ub() as f64).abs().max(1.0);
+                (ll.explored() as f64, gap / scale)
+            })
+            .collect()
+    }
+    /// The total improvement of the lower bound accumulated so far, at each
+    /// explored count: it starts at 0 and adds up every increase of the lower
+    /// bound (the increases from a sentinel lower bound are not accounted for).
+    pub fn cumulative_lb_gain(&self) -> Vec<(f64, f64)> {
+        let mut gain = 0.0;
+        let mut prev = self.lines.first().map(LogLine::lb);
+        self.lines.iter()
+            .map(|ll| {
+                if let Some(prev) = prev.filter(|prev| !is_sentinel(*prev)) {
+                    gain += (ll.lb() as f64 - prev as f64).max(0.0);
+                }
+                prev = Some(ll.lb());
+                (ll.explored() as f64, gain)
+            })
+            .collect()
+    }
+    /// The lines whose lower bound exceeds the upper bound. A correct solver
+    /// never logs such a line: each of them reveals a bug.
+    pub fn crossings(&self) -> Vec<&LogLine> {
+        self.lines.iter().filter(|ll| ll.lb() > ll.ub()).collect()
+    }
+    /// The number of distinct upper bounds seen so far, at each explored
+    /// count. This staircase flattens when the upper bound stops improving
+    /// (the sentinel upper bounds are not counted).
+    pub fn distinct_ub_count(&self) -> Vec<(f64, f64)> {
+        let mut seen = HashSet::new();
+        self.lines.iter()
+            .map(|ll| {
+                if !is_sentinel(ll.ub()) {
+                    seen.insert(ll.ub());
+                }
+                (ll.explored() as f64, seen.len() as f64)
+            })
+            .collect()
+    }
+    /// The rate at which the gap closes: the difference of the gaps of two
+    /// consecutive lines divided by the number of nodes explored in between.
+    /// The lines which explored no new node are skipped (the rate would be
+    /// undefined). A long stretch close to zero reveals a stall.
+    pub fn gap_velocity(&self) -> Vec<(f64, f64)> {
+        self.gap_explored()
+            .windows(2)
+            .filter(|w| w[1].0 > w[0].0)
+            .map(|w| (w[1].0, (w[1].1 - w[0].1) / (w[1].0 - w[0].0)))
+            .collect()
+    }
+    /// The fraction of the consecutive ongoing lines between which the fringe
+    /// grew. The fringe should shrink near the end of the search: when it grows
+    /// most of the time, the run is unlikely to terminate in reasonable memory.
+    /// This is 0 when the trace has less than two ongoing lines.
+    pub fn fringe_growing_fraction(&self) -> f64 {
+        let fringes = self.lines.iter().filter(|ll| ll.is_ongoing()).map(LogLine::fringe).collect::<Vec<usize>>();
+        let steps   = fringes.len().saturating_sub(1);
+        if steps == 0 {
+            return 0.0;
+        }
+        let growing = fringes.windows(2).filter(|w| w[1] > w[0]).count();
+        growing as f64 / steps as f64
+    }
+    /// The area under the gap (as plotted by the gap view) over the explored
+    /// nodes, integrated with the trapezoidal rule over the ongoing lines.
+    /// The lower, the faster the gap was closed. This is 0 when the trace has
+    /// less than two ongoing lines.
+    pub fn area_under_gap(&self) -> f64 {
+        self.filter(LogLine::is_ongoing).gap_explored()
+            .windows(2)
+            .map(|w| (w[1].0 - w[0].0) * (w[0].1 + w[1].1) / 2.0)
+            .sum()
+    }
+    /// Estimates how fast the gap shrinks. This fits `gap ~ exp(-k * explored)`
+    /// with a least squares regression on the log of the gap, and returns `k`.
+    /// Zero and negative gaps are clamped to `GAP_FLOOR` so that the log stays
+    /// defined. Returns None when there aren't at least two distinct explored
+    /// counts to fit the curve on.
+    pub fn gap_decay_rate(&self) -> Option<f64> {
+        let points = self.lines.iter()
+            .map(|ll| {
+                let gap = ll.ub() as f64 - ll.lb() as f64;
+                (ll.explored() as f64, gap.max(GAP_FLOOR).ln())
+            })
+            .collect::<Vec<(f64, f64)>>();
+
+        if points.len() < 2 {
+            return None;
+        }
+
+        let n      = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let sxy    = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>();
+        let sxx    = points.iter().map(|(x, _)| (x - mean_x) * (x - mean_x)).sum::<f64>();
+
+        if sxx == 0.0 {
+            None
+        } else {
+            Some(-sxy / sxx)
+        }
+    }
+    /// Cleans up a trace whose lines were logged by several threads and are
+    /// thus interleaved (the explored counts are not monotonic). Each line
+    /// of the resulting trace reports the largest explored count and the
+    /// tightest bounds seen so far. Lines which do not increase the explored
+    /// count are merged with the previous one.
+    pub fn monotone_envelope(&self) -> Trace {
+        let mut lines: Vec<LogLine> = vec![];
+        let mut explored = 0;
+        let mut lb       = i32::MIN;
+        let mut ub       = i32::MAX;
+        for line in self.lines.iter() {
+            explored = explored.max(line.explored());
+            lb       = lb.max(line.lb());
+            ub       = ub.min(line.ub());
+            let best = match *line {
+                LogLine::Ongoing {fringe, segment, time, ..} =>
+                    LogLine::Ongoing {explored, lb, ub, fringe, segment, time},
+                LogLine::Final   {opt_value, segment, time, ..} =>
+                    LogLine::Final   {explored, opt_value, segment, time}
+            };
+            if lines.last().is_some_and(|last| last.is_ongoing() && last.explored() == explored) {
+                lines.pop();
+            }
+            lines.push(best);
+        }
+        self.with_lines(lines)
+    }
+    /// Splits this trace in as many traces as there are segments (restarts)
+    pub fn segments(&self) -> Vec<Trace> {
+        let mut segments: Vec<Trace> = vec![];
+        for line in self.lines.iter() {
+            match segments.last_mut() {
+                Some(current) if current.lines[0].segment() == line.segment() =>
+                    current.lines.push(*line),
+                _ =>
+                    segments.push(self.with_lines(vec![*line]))
+            }
+        }
+        segments
+    }
+    /// Summarizes the state of the search at the last line of the trace
+    pub fn stats(&self) -> Option<TraceStats> {
+        self.lines.last().map(|last| TraceStats {
+            name      : self.name.clone(),
+            explored  : last.explored(),
+            lb        : last.lb(),
+            ub        : last.ub(),
+            gap       : last.ub().saturating_sub(last.lb()),
+            proven    : self.is_proven(),
+            decay_rate: self.gap_decay_rate(),
+            gap_area  : self.area_under_gap(),
+            time      : self.duration(),
+            fringe_growing: self.fringe_growing_fraction()
+        })
+    }
+}
+
+/// Keeps the first occurrence of each distinct value of the series
+fn milestones(series: impl Iterator<Item=(i32, usize)>) -> Vec<(i32, usize)> {
+    let mut result: Vec<(i32, usize)> = vec![];
+    for (value, explored) in series {
+        if !result.iter().any(|(v, _)| *v == value) {
+            result.push((value, explored));
+        }
+    }
+    result
+}
+
+/// Linearly interpolates the value of the series (sorted by x) at `x`.
+/// There is no extrapolation: None is returned when `x` is out of range.
+fn interpolate(points: &[(f64, f64)], x: f64) -> Option<f64> {
+    let (first, _) = points.first()?;
+    let (last,  _) = points.last()?;
+    if x < *first || x > *last {
+        return None;
+    }
+    if points.len() == 1 {
+        return Some(points[0].1);
+    }
+    points.windows(2)
+        .find(|w| w[0].0 <= x && x <= w[1].0)
+        .map(|w| {
+            let (x0, y0) = w[0];
+            let (x1, y1) = w[1];
+            if x1 == x0 { y0 } else { y0 + (y1 - y0) * (x - x0) / (x1 - x0) }
+        })
+}
+
+// --------------------------------------------------------------------------- //
+/// The envelope of the upper bounds of several traces (e.g. repeated runs of
+/// the same instance) sampled at common explored counts.
+// --------------------------------------------------------------------------- //
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UbBand {
+    pub min   : Points,
+    pub median: Points,
+    pub max   : Points
+}
+
+/// Resamples the upper bounds of the traces at `samples` evenly spaced
+/// explored counts spanning all of them, and aggregates the traces which
+/// cover each count (see `Trace::ub_at`).
+pub fn ub_band(traces: &[Trace], samples: usize) -> UbBand {
+    let explored = traces.iter().flat_map(|trace| trace.lines.iter()).map(|ll| ll.explored());
+    let (first, last) = match (explored.clone().min(), explored.max()) {
+        (Some(first), Some(last)) => (first as f64, last as f64),
+        _                         => return UbBand::default()
+    };
+    let step = if samples > 1 { (last - first) / (samples - 1) as f64 } else { 0.0 };
+
+    let mut band = UbBand::default();
+    for i in 0..samples.max(1) {
+        let x = (first + i as f64 * step).round();
+        let mut ubs = traces.iter()
+            .filter_map(|trace| trace.ub_at(x as usize))
+            .collect::<Vec<f64>>();
+        if ubs.is_empty() {
+            continue;
+        }
+        ubs.sort_by(|a, b| a.total_cmp(b));
+        let n      = ubs.len();
+        let median = if n % 2 == 1 { ubs[n / 2] } else { (ubs[n / 2 - 1] + ubs[n / 2]) / 2.0 };
+        band.min.push((x, ubs[0]));
+        band.median.push((x, median));
+        band.max.push((x, ubs[n - 1]));
+    }
+    band
+}
+
+/// The fraction of its span by which a plotted range is widened on each side
+const RANGE_PADDING: f64 = 0.05;
+
+/// The magnitude from which a bound is deemed to be a sentinel standing for an
+/// infinite bound (solvers log `i32::MIN`, `-i32::MAX` or alike before any
+/// actual bound is known) rather than an actual bound
+pub const SENTINEL_MAGNITUDE: i32 = 1_000_000_000;
+
+/// True iff the bound is a sentinel (see `SENTINEL_MAGNITUDE`)
+pub fn is_sentinel(bound: i32) -> bool {
+    bound <= -SENTINEL_MAGNITUDE || bound >= SENTINEL_MAGNITUDE
+}
+
+/// Smoothes the series with a moving average: each y is replaced by the mean
+/// of the (at most) `window` values ending at that point.
+pub fn smoothed(points: Points, window: usize) -> Points {
+    let window = window.max(1);
+    (0..points.len())
+        .map(|i| {
+            let span = &points[(i + 1).saturating_sub(window)..=i];
+            (points[i].0, span.iter().map(|(_, y)| y).sum::<f64>() / span.len() as f64)
+        })
+        .collect()
+}
+
+/// Splits the points in those whose y lies below the cap and those whose y lies
+/// above it. The latter are moved down to the cap.
+pub fn clamped(points: Points, cap: f64) -> (Points, Points) {
+    let (kept, clamped): (Points, Points) = points.into_iter()
+        .partition(|(_, y)| *y <= cap);
+    (kept, clamped.into_iter().map(|(x, _)| (x, cap)).collect())
+}
+
+/// Widens the range by `RANGE_PADDING` of its span on each side, so that the
+/// extreme points are not drawn on the axes. A flat range (which plotlib
+/// cannot draw) is widened by one unit on each side instead.
+pub fn padded(min: f64, max: f64) -> (f64, f64) {
+    let pad = if max > min { (max - min) * RANGE_PADDING } else { 1.0 };
+    (min - pad, max + pad)
+}
+
+/// The value to which null or negative gaps are clamped when taking their log.
+/// Since bounds are integral, half a unit is smaller than any actual gap.
+const GAP_FLOOR: f64 = 0.5;
+
+/// The fraction of the steps in which the fringe grew above which a trace is
+/// flagged as unlikely to terminate in reasonable memory
+pub const FRINGE_GROWING_THRESHOLD: f64 = 0.6;
+
+// --------------------------------------------------------------------------- //
+/// The headline numbers of a trace: where the search was at when it stopped.
+// --------------------------------------------------------------------------- //
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStats {
+    pub name      : Option<String>,
+    pub explored  : usize,
+    pub lb        : i32,
+    pub ub        : i32,
+    pub gap       : i32,
+    pub proven    : bool,
+    pub decay_rate: Option<f64>,
+    pub gap_area  : f64,
+    pub time      : Option<f64>,
+    /// The fraction of the steps in which the fringe grew
+    pub fringe_growing: f64
 }
 
 // --------------------------------------------------------------------------- //
 // Parsing d'une trace
 // --------------------------------------------------------------------------- //
+impl Trace {
+    /// Parses one log line and appends it to this trace (in the same segment
+    /// as the last line). This is meant to build a trace incrementally, as
+    /// its lines are streamed. Returns whether the line was appended, that is
+    /// false when it is not a log line.
+    pub fn push_line(&mut self, line: &str) -> bool {
+        let segment = self.lines.last().map_or(0, LogLine::segment);
+        match LogLine::try_from(line) {
+            Ok(logline) => { self.lines.push(logline.in_segment(segment)); true },
+            Err(_)      => false
+        }
+    }
+    /// Parses one line of a trace and appends it to this trace. Restart
+    /// markers are not log lines per se, but they bump the current `segment`
+    /// which is used to tag the subsequent lines. The summary line gives the
+    /// solve time. The solution block is made of a `### Solution:` header
+    /// followed by the values of the assignment, on one line. The kind of the
+    /// line is tallied in the stats.
+    fn parse_line(&mut self, segment: &mut usize, line: &str, format: &LogFormat, stats: &mut ParseStats) {
+        if RESTART_EXP.is_match(line) {
+            *segment += 1;
+        } else if let Some(seconds) = format.parse_computed_in(line) {
+            self.seconds = Some(seconds);
+        } else if SOLUTION_EXP.is_match(line) {
+            self.solution = Some(vec![]);
+        } else if self.solution.as_ref().is_some_and(Vec::is_empty) && ASSIGNMENT_EXP.is_match(line) {
+            self.solution = Some(line.split_whitespace().filter_map(|v| v.parse().ok()).collect());
+        } else if let Some(logline) = format.parse(line) {
+            if logline.is_final() {
+                stats.final_ += 1;
+            } else {
+                stats.ongoing += 1;
+            }
+            self.lines.push(logline.in_segment(*segment));
+        } else if !line.trim().is_empty() {
+            trace!("ignored the line '{}'", line);
+            stats.skipped += 1;
+        }
+    }
+}
+// --------------------------------------------------------------------------- //
+/// One line of a trace written as a json object (NDJSON), e.g.
+/// `{"explored": 100, "lb": 0, "ub": 40, "fringe": 10}`
+// --------------------------------------------------------------------------- //
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct JsonLine {
+    pub explored: usize,
+    pub lb      : i32,
+    pub ub      : i32,
+    pub fringe  : usize
+}
+impl From<JsonLine> for LogLine {
+    fn from(line: JsonLine) -> Self {
+        LogLine::Ongoing {
+            explored: line.explored,
+            lb      : line.lb,
+            ub      : line.ub,
+            fringe  : line.fringe,
+            segment : 0,
+            time    : None
+        }
+    }
+}
+/// The extension of the files which are read as json lines
+pub const NDJSON_EXTENSION: &str = "ndjson";
+impl Trace {
+    /// Parses one json line of a trace and appends it to this trace. The lines
+    /// which are not such objects are skipped (and tallied in the stats).
+    fn parse_json_line(&mut self, line: &str, stats: &mut ParseStats) {
+        if line.trim().is_empty() {
+            return;
+        }
+        match serde_json::from_str::<JsonLine>(line) {
+            Ok(json) => {
+                stats.ongoing += 1;
+                self.lines.push(json.into());
+            },
+            Err(e) => {
+                trace!("ignored the line '{}': {}", line, e);
+                stats.skipped += 1;
+            }
+        }
+    }
+}
+// --------------------------------------------------------------------------- //
+/// The number of lines of each kind met while parsing a trace
+// --------------------------------------------------------------------------- //
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    /// The number of lines reporting the progress of the search
+    pub ongoing: usize,
+    /// The number of `Final` lines
+    pub final_ : usize,
+    /// The number of (non blank) lines which were not recognized at all
+    pub skipped: usize
+}
+// --------------------------------------------------------------------------- //
+/// A log line which does not read the same once parsed and written back in
+/// the canonical wording of ddo (see `round_trip_check`)
+// --------------------------------------------------------------------------- //
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundTripMismatch {
+    /// The number of the line in the log (starting at 1)
+    pub line_no: usize,
+    /// The line as it was read (trimmed and without its ansi escape codes)
+    pub read   : String,
+    /// The line as it is written back once parsed
+    pub written: String
+}
+/// Parses each selected line of the log in the wording of the options and
+/// writes it back in the canonical wording of ddo (see `Display`). The lines
+/// which then differ from what was read are returned, along with the number
+/// of lines which were parsed. Reading stops at the first io error.
+pub fn round_trip_check(lines: impl Iterator<Item=std::io::Result<String>>, options: &ReadOptions) -> std::io::Result<(usize, Vec<RoundTripMismatch>)> {
+    let mut parsed     = 0;
+    let mut mismatches = vec![];
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let line = strip_ansi(&line);
+        if !options.selects(&line) {
+            continue;
+        }
+        let read = line.trim();
+        if let Some(ll) = options.format.parse(read) {
+            parsed += 1;
+            let written = ll.to_string();
+            if written != read {
+                mismatches.push(RoundTripMismatch { line_no: i + 1, read: read.to_string(), written });
+            }
+        }
+    }
+    Ok((parsed, mismatches))
+}
+/// Parses the duration of a summary line (`... computed in 1.2ms`) and
+/// converts it to seconds. The duration may be expressed in `s`, `ms`, `µs`
+/// (or `us`) and `ns`.
+pub fn parse_computed_in(line: &str) -> Option<f64> {
+    LogFormat::latest().parse_computed_in(line)
+}
 impl From<&str> for Trace {
     fn from(lines: &str) -> Self {
-        let mut result = Trace{ name: None, lines: vec![]};
+        let mut result = Trace{ name: None, lines: vec![], seconds: None, solution: None };
+        let mut segment= 0;
+        let mut stats  = ParseStats::default();
         for line in lines.lines() {
-            if let Ok(logline) = LogLine::try_from(line) {
-                result.lines.push(logline);
-            }
+            result.parse_line(&mut segment, &strip_ansi(line), LogFormat::latest(), &mut stats);
         }
         result
     }
 }
-impl <'a, X: BufRead> From<Lines<X>> for Trace {
-    fn from(lines: Lines<X>) -> Self {
-        let mut result = Trace{ name: None, lines: vec![]};
-        for line in lines {
-            let line = line.unwrap();
-            if let Ok(logline) = LogLine::try_from(line.as_str()) {
-                result.lines.push(logline);
+/// The error raised when reading a trace fails before its end was reached
+/// (e.g. invalid utf-8 or a disconnected pipe). It retains the part of the
+/// trace which was read before the failure.
+#[derive(Debug)]
+pub struct TruncatedTrace {
+    /// The trace parsed from the lines read before the failure
+    pub trace     : Trace,
+    /// The number of lines which were successfully read
+    pub lines_read: usize,
+    /// The io error which stopped the reading
+    pub error     : std::io::Error
+}
+impl fmt::Display for TruncatedTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (after reading {} lines)", self.error, self.lines_read)
+    }
+}
+impl From<TruncatedTrace> for std::io::Error {
+    fn from(truncated: TruncatedTrace) -> Self {
+        std::io::Error::new(truncated.error.kind(), truncated.to_string())
+    }
+}
+impl Trace {
+    /// Parses the trace from the given lines (e.g. `BufRead::lines` or
+    /// `BoundedLines`). Reading stops at the first io error, in which case the
+    /// partial trace is returned along with the error.
+    pub fn try_from_lines(lines: impl Iterator<Item=std::io::Result<String>>) -> Result<Trace, TruncatedTrace> {
+        Trace::try_from_lines_with(lines, &ReadOptions::default())
+    }
+    /// Parses the trace from the given lines as per the options: only the
+    /// selected lines are parsed, in the wording of the given version of ddo
+    /// (see `try_from_lines`).
+    pub fn try_from_lines_with(lines: impl Iterator<Item=std::io::Result<String>>, options: &ReadOptions) -> Result<Trace, TruncatedTrace> {
+        let (trace, stats) = Trace::parse_strict(lines, options)?;
+        debug!("parsed {} ongoing and {} final line(s), {} line(s) were not recognized",
+               stats.ongoing, stats.final_, stats.skipped);
+        Ok(trace)
+    }
+    /// Parses the trace from the given lines as per the options, and counts
+    /// the lines of each kind along the way. This is strict in that the first
+    /// io error fails the parsing (the partial trace comes along with it).
+    pub fn parse_strict(lines: impl Iterator<Item=std::io::Result<String>>, options: &ReadOptions) -> Result<(Trace, ParseStats), TruncatedTrace> {
+        let mut result = Trace{ name: None, lines: vec![], seconds: None, solution: None };
+        let mut segment= 0;
+        let mut stats  = ParseStats::default();
+        for (lines_read, line) in lines.enumerate() {
+            let line = match line {
+                Ok(line)   => line,
+                Err(error) => return Err(TruncatedTrace { trace: result, lines_read, error })
+            };
+            let line = strip_ansi(&line);
+            if !options.selects(&line) {
+                continue;
+            }
+            if options.ndjson {
+                result.parse_json_line(&line, &mut stats);
+            } else {
+                result.parse_line(&mut segment, &line, options.format, &mut stats);
             }
         }
-        result
+        Ok((result, stats))
+    }
+}
+/// Parses the trace from the given lines. When an io error occurs, the part
+/// of the trace read until then is kept (use `Trace::try_from_lines` to know
+/// whether the trace was read completely).
+impl <X: BufRead> From<Lines<X>> for Trace {
+    fn from(lines: Lines<X>) -> Self {
+        Trace::try_from_lines(lines).unwrap_or_else(|truncated| truncated.trace)
     }
 }
 impl From<File> for Trace {
@@ -151,26 +1174,243 @@ impl TryFrom<&Path> for Trace {
     type Error=std::io::Error;
 
     fn try_from(path: &Path) -> Result<Self, Self::Error> {
-        let stem  = path.file_stem().map(|f| f.to_string_lossy().to_string());
-        let mut trace = Trace::from(File::open(path)?);
-        trace.name = stem;
-        Ok(trace)
+        Trace::load(path, &ReadOptions::default()).map(|(trace, _)| trace)
+    }
+}
+impl Trace {
+    /// Reads the trace from the given file and names it after that file. The
+    /// lines longer than the maximum line length are skipped (without being
+    /// buffered). Returns the trace along with the number of skipped lines.
+    /// The files with an `.ndjson` extension are read as json lines. A file
+    /// which cannot be read to its end yields the part of the trace read until
+    /// then (the error is reported as a warning).
+    pub fn load(path: &Path, options: &ReadOptions) -> Result<(Trace, usize), std::io::Error> {
+        let ndjson  = ReadOptions { ndjson: true, ..options.clone() };
+        let options = if path.extension().is_some_and(|ext| ext == NDJSON_EXTENSION) { &ndjson } else { options };
+        let (trace, skipped) = parse_file(File::open(path)?, path, options)?;
+        match path.file_stem() {
+            Some(stem) => Ok((trace.with_name(stem.to_string_lossy()), skipped)),
+            None       => Ok((trace, skipped))
+        }
     }
 }
 
+// --------------------------------------------------------------------------- //
+/// The options which tune the way the lines of a trace are read
+// --------------------------------------------------------------------------- //
+#[derive(Debug, Clone)]
+pub struct ReadOptions {
+    /// The length (in bytes) above which a line is skipped
+    pub max_line_length: usize,
+    /// The wording of the log lines
+    pub format         : &'static LogFormat,
+    /// If set, only the lines matching this expression are parsed (e.g. the
+    /// lines of one worker of a multi-threaded solver)
+    pub select         : Option<Regex>,
+    /// The lines are json objects (one per line) rather than ddo log lines
+    pub ndjson         : bool
+}
+impl Default for ReadOptions {
+    fn default() -> Self {
+        ReadOptions {
+            max_line_length: MAX_LINE_LENGTH,
+            format         : LogFormat::latest(),
+            select         : None,
+            ndjson         : false
+        }
+    }
+}
+impl ReadOptions {
+    /// True iff the given raw line is to be parsed
+    pub fn selects(&self, line: &str) -> bool {
+        self.select.as_ref().is_none_or(|select| select.is_match(line))
+    }
+}
 
-#[cfg(test)]
-mod test {
-    use std::convert::TryFrom;
-
-    use crate::data::{LogLine, Trace};
+/// The default length (in bytes) above which a line is skipped while reading
+/// a trace. A log line is much shorter than that: a longer line can only come
+/// from a malformed log (which should not have to be buffered whole).
+pub const MAX_LINE_LENGTH: usize = 64 * 1024;
 
-    #[test]
-    fn parse_final_line() {
-        let parsed = LogLine::try_from("Final 11, Explored 6790").unwrap();
+// --------------------------------------------------------------------------- //
+/// An iterator over the lines of a reader which, unlike `BufRead::lines`,
+/// skips the lines longer than a maximum length without buffering them. It
+/// counts the lines it skipped.
+// --------------------------------------------------------------------------- //
+pub struct BoundedLines<R> {
+    reader : R,
+    max_len: usize,
+    /// The number of lines which were skipped so far
+    pub skipped: usize
+}
+impl <R: BufRead> BoundedLines<R> {
+    pub fn new(reader: R, max_len: usize) -> Self {
+        BoundedLines { reader, max_len, skipped: 0 }
+    }
+}
+impl <R: BufRead> Iterator for BoundedLines<R> {
+    type Item = std::io::Result<String>;
 
-        assert_eq!(11,   parsed.lb());
-        assert_eq!(11,   parsed.ub());
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut line     = vec![];
+            let mut too_long = false;
+            let mut read_any = false;
+            loop {
+                let (used, eol) = {
+                    let buf = match self.reader.fill_buf() {
+                        Ok(buf) => buf,
+                        Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                        Err(e) => return Some(Err(e))
+                    };
+                    if buf.is_empty() {
+                        break;
+                    }
+                    let eol   = buf.iter().position(|b| *b == b'\n');
+                    let chunk = &buf[..eol.unwrap_or(buf.len())];
+                    if line.len() + chunk.len() > self.max_len {
+                        too_long = true;
+                        line.clear();
+                    } else if !too_long {
+                        line.extend_from_slice(chunk);
+                    }
+                    (eol.map_or(buf.len(), |i| i + 1), eol.is_some())
+                };
+                read_any = true;
+                self.reader.consume(used);
+                if eol {
+                    break;
+                }
+            }
+            if !read_any {
+                return None;
+            }
+            if too_long {
+                debug!("skipped a line longer than {} bytes", self.max_len);
+                self.skipped += 1;
+                continue;
+            }
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            return Some(String::from_utf8(line).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e)));
+        }
+    }
+}
+
+/// The size (in bytes) above which a progress bar is shown while parsing a file
+#[cfg(feature = "progress")]
+const PROGRESS_THRESHOLD: u64 = 64 * 1024 * 1024;
+
+/// The trace read from the given file. When the file could not be read to its
+/// end, the part of the trace read until then is kept and the error is
+/// reported as a warning.
+fn partial(trace: Result<Trace, TruncatedTrace>, path: &Path) -> Trace {
+    trace.unwrap_or_else(|truncated| {
+        warn!("the file '{}' could not be read to its end: {}", path.display(), truncated);
+        truncated.trace
+    })
+}
+
+/// Parses the trace from the given file, skipping the lines longer than
+/// `max_len`. A file which cannot be read to its end yields the part of the
+/// trace read until then (see `partial`). Returns the trace along with the
+/// number of skipped lines.
+#[cfg(not(feature = "progress"))]
+fn parse_file(file: File, path: &Path, options: &ReadOptions) -> Result<(Trace, usize), std::io::Error> {
+    let mut lines = BoundedLines::new(BufReader::new(file), options.max_line_length);
+    let trace     = partial(Trace::try_from_lines_with(&mut lines, options), path);
+    Ok((trace, lines.skipped))
+}
+/// Parses the trace from the given file. When the file is large and the
+/// program runs in a terminal, a progress bar shows how much of the file has
+/// already been read.
+#[cfg(feature = "progress")]
+fn parse_file(file: File, path: &Path, options: &ReadOptions) -> Result<(Trace, usize), std::io::Error> {
+    use indicatif::{ProgressBar, ProgressStyle};
+    use std::io::IsTerminal;
+
+    let size = file.metadata()?.len();
+    if size < PROGRESS_THRESHOLD || !std::io::stderr().is_terminal() {
+        let mut lines = BoundedLines::new(BufReader::new(file), options.max_line_length);
+        let trace     = partial(Trace::try_from_lines_with(&mut lines, options), path);
+        return Ok((trace, lines.skipped));
+    }
+
+    let style = ProgressStyle::with_template("{msg} [{wide_bar}] {bytes}/{total_bytes} ({eta})")
+        .expect("Invalid progress bar template");
+    let bar   = ProgressBar::new(size).with_style(style);
+    bar.set_message(path.display().to_string());
+    let mut lines = BoundedLines::new(BufReader::new(bar.wrap_read(file)), options.max_line_length);
+    let trace     = Trace::try_from_lines_with(&mut lines, options);
+    bar.finish_and_clear();
+    Ok((partial(trace, path), lines.skipped))
+}
+
+// --------------------------------------------------------------------------- //
+/// A reference curve read from an external csv file (e.g. the bound
+/// progression of another solver) which is overlaid on the bounds.
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Debug)]
+pub struct Overlay {
+    pub name  : String,
+    pub points: Vec<(f64, f64)>
+}
+
+impl Overlay {
+    /// Parses the `explored,value` rows of a csv text. Blank lines and a
+    /// leading header are ignored; the malformed rows are skipped. Returns
+    /// the overlay along with the number of rows that were skipped.
+    pub fn parse(name: &str, text: &str) -> (Overlay, usize) {
+        let mut points  = vec![];
+        let mut skipped = 0;
+        for (i, row) in text.lines().enumerate() {
+            let row = row.trim();
+            if row.is_empty() {
+                continue;
+            }
+            match Overlay::parse_row(row) {
+                Some(point) => points.push(point),
+                None if i == 0 => {},
+                None => skipped += 1
+            }
+        }
+        (Overlay { name: name.to_string(), points }, skipped)
+    }
+    fn parse_row(row: &str) -> Option<(f64, f64)> {
+        let mut cols = row.split(',').map(str::trim);
+        let explored = cols.next()?.parse::<f64>().ok()?;
+        let value    = cols.next()?.parse::<f64>().ok()?;
+        if cols.next().is_some() {
+            None
+        } else {
+            Some((explored, value))
+        }
+    }
+    /// Reads the overlay from a csv file and names it after that file.
+    /// Returns the overlay along with the number of rows that were skipped.
+    pub fn load(path: &Path) -> Result<(Overlay, usize), std::io::Error> {
+        let stem = path.file_stem().map_or("overlay".to_string(), |f| f.to_string_lossy().to_string());
+        Ok(Overlay::parse(&stem, &read_to_string(path)?))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+    use std::io::{BufRead, BufReader, Read};
+
+    use regex::Regex;
+
+    use crate::data::{padded, parse_computed_in, round_trip_check, smoothed, strip_ansi, ub_band, BoundedLines, CsvColumn, LogFormat, LogLine, Overlay, ParseStats, ReadOptions, RoundTripMismatch, Trace, TraceStats, DEFAULT_CSV_COLUMNS, LATEST_VERSION};
+
+    #[test]
+    fn parse_final_line() {
+        let parsed = LogLine::try_from("Final 11, Explored 6790").unwrap();
+
+        assert_eq!(11,   parsed.lb());
+        assert_eq!(11,   parsed.ub());
         //assert_eq!(0 ,   parsed.fringe());
         assert_eq!(6790, parsed.explored());
     }
@@ -185,6 +1425,16 @@ mod test {
         assert_eq!(6700, parsed.explored());
     }
     #[test]
+    fn kind_of_the_parsed_lines() {
+        let last    = LogLine::try_from("Final 11, Explored 6790").unwrap();
+        let ongoing = LogLine::try_from("Explored 6700, LB 11, UB 12, Fringe sz 90").unwrap();
+
+        assert!(last.is_final());
+        assert!(!last.is_ongoing());
+        assert!(ongoing.is_ongoing());
+        assert!(!ongoing.is_final());
+    }
+    #[test]
     fn parse_final_line_with_negatives() {
         let line   = "Final -11, Explored 6790";
         let parsed = LogLine::try_from(line).unwrap();
@@ -205,6 +1455,64 @@ mod test {
         assert_eq!(6700, parsed.explored());
     }
 
+    /// A tiny deterministic pseudo random generator (xorshift) for the
+    /// property tests
+    struct Rng(u64);
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        fn usize(&mut self) -> usize { self.next() as usize }
+        fn i32(&mut self)   -> i32   { self.next() as i32 }
+    }
+
+    #[test]
+    fn display_canonical_format() {
+        let ongoing = LogLine::try_from("Explored 6700, LB 11, UB 12, Fringe sz 90").unwrap();
+        let fin     = LogLine::try_from("Final 11, Explored 6790").unwrap();
+
+        assert_eq!("Explored 6700, LB 11, UB 12, Fringe sz 90", ongoing.to_string());
+        assert_eq!("Final 11, Explored 6790",                    fin.to_string());
+    }
+    #[test]
+    fn display_round_trips() {
+        let mut rng = Rng(0x5eed_cafe_d00d_f00d);
+        for _ in 0..1000 {
+            let ongoing = LogLine::Ongoing {
+                explored: rng.usize(),
+                lb      : rng.i32(),
+                ub      : rng.i32(),
+                fringe  : rng.usize(),
+                segment : 0,
+                time    : None
+            };
+            let fin = LogLine::Final {
+                explored : rng.usize(),
+                opt_value: rng.i32(),
+                segment  : 0,
+                time     : Some((rng.usize() % 100_000) as f64 / 1000.0)
+            };
+            assert_eq!(Ok(ongoing), LogLine::try_from(ongoing.to_string().as_str()));
+            assert_eq!(Ok(fin),     LogLine::try_from(fin.to_string().as_str()));
+        }
+    }
+
+    #[test]
+    fn parse_timestamped_lines() {
+        let ongoing = LogLine::try_from("[12.5s] Explored 6700, LB 11, UB 12, Fringe sz 90").unwrap();
+        let fin     = LogLine::try_from("[13] Final 11, Explored 6790").unwrap();
+        let plain   = LogLine::try_from("Final 11, Explored 6790").unwrap();
+
+        assert_eq!(Some(12.5), ongoing.time());
+        assert_eq!(Some(13.0), fin.time());
+        assert_eq!(None,       plain.time());
+        assert_eq!(6700,       ongoing.explored());
+        assert_eq!("[12.5s] Explored 6700, LB 11, UB 12, Fringe sz 90", ongoing.to_string());
+    }
+
     #[test]
     fn when_it_fails() {
         let line   = "Coucou ca va ?";
@@ -242,4 +1550,854 @@ Optimum 11 computed in 5.042205s with 1 threads
         assert_eq!(10, trace.lines.len());
     }
 
+    #[test]
+    fn bounds_at_are_interpolated() {
+        let log   = "
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 10, UB 20, Fringe sz 20
+Final 15, Explored 300
+";
+        let trace = Trace::from(log);
+        // endpoints
+        assert_eq!(Some(30.0), trace.ub_at(100));
+        assert_eq!(Some(0.0),  trace.lb_at(100));
+        assert_eq!(Some(15.0), trace.ub_at(300));
+        assert_eq!(Some(15.0), trace.lb_at(300));
+        // on a line
+        assert_eq!(Some(20.0), trace.ub_at(200));
+        assert_eq!(Some(10.0), trace.lb_at(200));
+        // between lines
+        assert_eq!(Some(25.0), trace.ub_at(150));
+        assert_eq!(Some(5.0),  trace.lb_at(150));
+        assert_eq!(Some(17.5), trace.ub_at(250));
+        assert_eq!(Some(12.5), trace.lb_at(250));
+    }
+    #[test]
+    fn bounds_at_do_not_extrapolate() {
+        let log   = "
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 10, UB 20, Fringe sz 20
+";
+        let trace = Trace::from(log);
+        assert_eq!(None, trace.ub_at(99));
+        assert_eq!(None, trace.lb_at(201));
+        assert_eq!(None, Trace::from("").ub_at(0));
+
+        let single = Trace::from("Explored 100, LB 0, UB 30, Fringe sz 10");
+        assert_eq!(Some(30.0), single.ub_at(100));
+        assert_eq!(None,       single.ub_at(101));
+    }
+    #[test]
+    fn restart_markers_delimit_segments() {
+        let log   = "
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 20
+--- restart 1 ---
+Explored 300, LB 7, UB 20, Fringe sz 30
+--- restart 2 ---
+Explored 400, LB 8, UB 12, Fringe sz 30
+Final 10, Explored 500
+";
+        let trace = Trace::from(log);
+        let tags  = trace.lines.iter().map(|ll| ll.segment()).collect::<Vec<usize>>();
+        assert_eq!(vec![0, 0, 1, 2, 2], tags);
+
+        let segments = trace.segments();
+        assert_eq!(3, segments.len());
+        assert_eq!(2, segments[0].lines.len());
+        assert_eq!(1, segments[1].lines.len());
+        assert_eq!(2, segments[2].lines.len());
+    }
+    #[test]
+    fn trace_without_markers_has_one_segment() {
+        let log   = "
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 20
+";
+        let trace = Trace::from(log);
+        assert!(trace.lines.iter().all(|ll| ll.segment() == 0));
+        assert_eq!(1, trace.segments().len());
+        assert!(Trace::from("").segments().is_empty());
+    }
+    #[test]
+    fn resample_time_keeps_the_last_line_per_bucket() {
+        let log   = "
+[0.1s] Explored 100, LB 0, UB 30, Fringe sz 10
+[0.5s] Explored 200, LB 1, UB 29, Fringe sz 10
+[1.2s] Explored 300, LB 2, UB 28, Fringe sz 10
+[1.9s] Explored 400, LB 3, UB 27, Fringe sz 10
+[3.5s] Explored 500, LB 4, UB 26, Fringe sz 10
+[3.6s] Final 10, Explored 600
+";
+        let trace = Trace::from(log);
+        assert!(trace.has_timestamps());
+
+        let explored = trace.resample_time(1.0).lines.iter()
+            .map(|ll| ll.explored())
+            .collect::<Vec<usize>>();
+        assert_eq!(vec![200, 400, 600], explored);
+    }
+    #[test]
+    fn traces_without_timestamps() {
+        let log   = "
+Explored 100, LB 0, UB 30, Fringe sz 10
+[0.5s] Explored 200, LB 1, UB 29, Fringe sz 10
+";
+        assert!(!Trace::from(log).has_timestamps());
+        assert!(!Trace::from("").has_timestamps());
+    }
+    #[test]
+    fn window_retains_the_lines_in_range() {
+        let log   = "
+Explored 100, LB 0, UB 1000, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 20
+Explored 300, LB 7, UB 12, Fringe sz 30
+Final 10, Explored 400
+";
+        let trace = Trace::from(log);
+
+        assert_eq!(4, trace.window(None, None).lines.len());
+        assert_eq!(2, trace.window(Some(200), Some(300)).lines.len());
+        assert_eq!(3, trace.window(Some(150), None).lines.len());
+        assert_eq!(1, trace.window(None, Some(199)).lines.len());
+        assert_eq!(0, trace.window(Some(500), None).lines.len());
+    }
+    #[test]
+    fn skip_drops_the_first_lines() {
+        let log   = "
+Explored 100, LB 0, UB 1000, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 20
+Explored 300, LB 7, UB 12, Fringe sz 30
+";
+        let trace = Trace::from(log);
+
+        let skipped = trace.skip(1);
+        assert_eq!(2,   skipped.lines.len());
+        assert_eq!(200, skipped.lines[0].explored());
+        assert_eq!(20,  skipped.lines[0].ub());
+
+        assert_eq!(3, trace.skip(0).lines.len());
+        assert_eq!(0, trace.skip(5).lines.len());
+    }
+    #[test]
+    fn milestones_of_the_bounds() {
+        let log   = "
+Explored 100, LB 5, UB 20, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 10
+Explored 300, LB 7, UB 18, Fringe sz 10
+Explored 400, LB 7, UB 15, Fringe sz 10
+Explored 500, LB 9, UB 15, Fringe sz 10
+Final 10, Explored 600
+";
+        let trace = Trace::from(log);
+        assert_eq!(vec![(20, 100), (18, 300), (15, 400), (10, 600)], trace.ub_milestones());
+        assert_eq!(vec![(5, 100), (7, 300), (9, 500), (10, 600)],    trace.lb_milestones());
+        assert!(Trace::from("").ub_milestones().is_empty());
+    }
+    #[test]
+    fn gaps_explored() {
+        let log   = "
+Explored 100, LB 5, UB 20, Fringe sz 10
+Explored 200, LB -3, UB 0, Fringe sz 10
+Explored 300, LB -12, UB -10, Fringe sz 10
+Final 10, Explored 400
+";
+        let trace = Trace::from(log);
+        assert_eq!(vec![(100.0, 15.0), (200.0, 3.0), (300.0, 2.0), (400.0, 0.0)],
+                   trace.gap_explored());
+        assert_eq!(vec![(100.0, 0.75), (200.0, 3.0), (300.0, 0.2), (400.0, 0.0)],
+                   trace.relative_gap_explored());
+    }
+    #[test]
+    fn gap_decay_rate_of_exponential_convergence() {
+        let log   = "
+Explored 0, LB 0, UB 1024, Fringe sz 10
+Explored 1, LB 0, UB 512, Fringe sz 10
+Explored 2, LB 0, UB 256, Fringe sz 10
+Explored 3, LB 0, UB 128, Fringe sz 10
+";
+        let rate = Trace::from(log).gap_decay_rate().unwrap();
+        assert!((rate - 2.0_f64.ln()).abs() < 1e-9);
+    }
+    #[test]
+    fn gap_decay_rate_clamps_null_gaps() {
+        let log   = "
+Explored 100, LB 10, UB 12, Fringe sz 10
+Explored 200, LB 11, UB 12, Fringe sz 10
+Final 12, Explored 300
+";
+        let rate = Trace::from(log).gap_decay_rate().unwrap();
+        assert!(rate.is_finite());
+        assert!(rate > 0.0);
+    }
+    #[test]
+    fn gap_decay_rate_needs_two_points() {
+        assert!(Trace::from("").gap_decay_rate().is_none());
+        assert!(Trace::from("Final 12, Explored 300").gap_decay_rate().is_none());
+    }
+    #[test]
+    fn parse_line_with_crlf() {
+        let parsed = LogLine::try_from("Explored 6700, LB 11, UB 12, Fringe sz 90\r").unwrap();
+
+        assert_eq!(6700, parsed.explored());
+        assert_eq!(90,   parsed.fringe());
+    }
+    #[test]
+    fn parse_line_with_leading_and_trailing_spaces() {
+        let parsed = LogLine::try_from("   \tFinal 11, Explored 6790  ").unwrap();
+
+        assert_eq!(11,   parsed.lb());
+        assert_eq!(6790, parsed.explored());
+    }
+    #[test]
+    fn stats_of_proven_trace() {
+        let log   = "
+Explored 6600, LB 11, UB 12, Fringe sz 190
+Explored 6700, LB 11, UB 12, Fringe sz 90
+Final 11, Explored 6790
+";
+        let trace = Trace::from(log);
+        let stats = trace.stats().unwrap();
+
+        assert_eq!(6790, stats.explored);
+        assert_eq!(0,    stats.gap);
+        assert!(stats.proven);
+    }
+    #[test]
+    fn stats_of_unproven_trace() {
+        let log   = "
+Explored 6600, LB 11, UB 14, Fringe sz 190
+Explored 6700, LB 11, UB 13, Fringe sz 90
+";
+        let trace = Trace::from(log);
+        let stats = trace.stats().unwrap();
+
+        assert_eq!(6700, stats.explored);
+        assert_eq!(2,    stats.gap);
+        assert!(!stats.proven);
+        assert!(Trace::from("").stats().is_none());
+    }
+    #[test]
+    fn bound_and_fringe_ranges() {
+        let log   = "
+Explored 100, LB -5, UB 40, Fringe sz 10
+Explored 200, LB 3, UB 20, Fringe sz 70
+Final 11, Explored 300
+";
+        let trace = Trace::from(log);
+
+        assert_eq!((-5, 40), trace.bound_range());
+        assert_eq!((0, 70),  trace.fringe_range());
+    }
+    #[test]
+    fn ranges_of_single_line_trace() {
+        let trace = Trace::from("Explored 100, LB 3, UB 20, Fringe sz 10");
+
+        assert_eq!((3, 20),  trace.bound_range());
+        assert_eq!((10, 10), trace.fringe_range());
+    }
+    #[test]
+    fn ranges_of_empty_trace() {
+        let trace = Trace::from("");
+
+        assert_eq!((0, 0), trace.bound_range());
+        assert_eq!((0, 0), trace.fringe_range());
+    }
+    #[test]
+    fn parse_overlay_csv() {
+        let csv = "explored,value
+100,40
+
+200, 30.5
+garbage
+300,20,10
+400,abc
+500,15
+";
+        let (overlay, skipped) = Overlay::parse("other", csv);
+
+        assert_eq!("other", overlay.name);
+        assert_eq!(vec![(100.0, 40.0), (200.0, 30.5), (500.0, 15.0)], overlay.points);
+        assert_eq!(3, skipped);
+    }
+    #[test]
+    fn padded_ranges() {
+        let log   = "
+Explored 100, LB 0, UB 40, Fringe sz 10
+Explored 200, LB 20, UB 30, Fringe sz 30
+";
+        let trace = Trace::from(log);
+
+        assert_eq!((9.0, 31.0),  trace.padded_fringe_range());
+    }
+    #[test]
+    fn padded_ranges_of_flat_and_empty_traces() {
+        assert_eq!((4.0, 6.0),   padded(5.0, 5.0));
+        assert_eq!((-1.0, 1.0),  Trace::from("").padded_fringe_range());
+    }
+    #[test]
+    fn clamp_ub_to_twice_the_optimum() {
+        let log   = "
+Explored 100, LB 0, UB 1000, Fringe sz 10
+Explored 200, LB 5, UB 15, Fringe sz 20
+Final 10, Explored 300
+";
+        let trace = Trace::from(log);
+        let cap   = trace.ub_cap(2.0).unwrap();
+        let (kept, clamped) = trace.ub_explored_clamped(cap);
+
+        assert_eq!(20.0, cap);
+        assert_eq!(vec![(200.0, 15.0), (300.0, 10.0)], kept);
+        assert_eq!(vec![(100.0, 20.0)], clamped);
+    }
+    #[test]
+    fn no_ub_cap_without_final_line() {
+        let trace = Trace::from("Explored 100, LB 0, UB 1000, Fringe sz 10");
+        assert_eq!(None, trace.ub_cap(2.0));
+    }
+    #[test]
+    fn monotone_envelope_of_interleaved_threads() {
+        let log   = "
+Explored 100, LB 0, UB 50, Fringe sz 10
+Explored 300, LB 5, UB 40, Fringe sz 30
+Explored 200, LB 8, UB 45, Fringe sz 20
+Explored 400, LB 6, UB 30, Fringe sz 40
+Explored 350, LB 7, UB 35, Fringe sz 35
+Final 20, Explored 500
+";
+        let clean = Trace::from(log).monotone_envelope();
+
+        let explored = clean.lines.iter().map(|ll| ll.explored()).collect::<Vec<usize>>();
+        let lbs      = clean.lines.iter().map(|ll| ll.lb()).collect::<Vec<i32>>();
+        let ubs      = clean.lines.iter().map(|ll| ll.ub()).collect::<Vec<i32>>();
+        assert_eq!(vec![100, 300, 400, 500], explored);
+        assert_eq!(vec![0, 8, 8, 20],        lbs);
+        assert_eq!(vec![50, 40, 30, 20],     ubs);
+        assert!(clean.lines[3].is_final());
+    }
+    #[test]
+    fn monotone_envelope_of_sequential_trace_is_unchanged() {
+        let log   = "
+Explored 100, LB 0, UB 50, Fringe sz 10
+Explored 200, LB 5, UB 40, Fringe sz 20
+Final 20, Explored 300
+";
+        let trace = Trace::from(log);
+        assert_eq!(trace.lines, trace.monotone_envelope().lines);
+    }
+    #[test]
+    fn ub_ratio_to_baseline() {
+        let baseline = Trace::from("
+Explored 100, LB 0, UB 40, Fringe sz 10
+Explored 300, LB 0, UB 20, Fringe sz 10
+");
+        let trace    = Trace::from("
+Explored 50, LB 0, UB 80, Fringe sz 10
+Explored 100, LB 0, UB 20, Fringe sz 10
+Explored 200, LB 0, UB 15, Fringe sz 10
+Explored 400, LB 0, UB 10, Fringe sz 10
+");
+        assert_eq!(vec![(100.0, 0.5), (200.0, 0.5)], trace.ub_ratio(&baseline));
+    }
+    #[test]
+    fn fringe_highwater_is_the_running_max() {
+        let log   = "
+Explored 100, LB 0, UB 50, Fringe sz 10
+Explored 200, LB 0, UB 50, Fringe sz 30
+Explored 300, LB 0, UB 50, Fringe sz 20
+Explored 400, LB 0, UB 50, Fringe sz 40
+Final 20, Explored 500
+";
+        assert_eq!(vec![(100.0, 10.0), (200.0, 30.0), (300.0, 30.0), (400.0, 40.0), (500.0, 40.0)],
+                   Trace::from(log).fringe_highwater());
+        assert!(Trace::from("").fringe_highwater().is_empty());
+    }
+    #[test]
+    fn filter_the_kinds_of_lines() {
+        let log   = "
+Explored 100, LB 0, UB 50, Fringe sz 10
+Final 20, Explored 200
+Explored 300, LB 0, UB 30, Fringe sz 10
+Final 25, Explored 400
+";
+        let trace = Trace::from(log);
+
+        let ongoing = trace.filter(LogLine::is_ongoing);
+        assert_eq!(vec![100, 300], ongoing.lines.iter().map(|ll| ll.explored()).collect::<Vec<_>>());
+        assert!(!ongoing.is_proven());
+
+        let finals  = trace.filter(LogLine::is_final);
+        assert_eq!(vec![200, 400], finals.lines.iter().map(|ll| ll.explored()).collect::<Vec<_>>());
+    }
+    #[test]
+    fn computed_in_is_normalized_to_seconds() {
+        let secs = |line| parse_computed_in(line).unwrap();
+
+        assert!((secs("Optimum 11 computed in 500ms") - 0.5).abs()    < 1e-12);
+        assert!((secs("Optimum 11 computed in 5s")    - 5.0).abs()    < 1e-12);
+        assert!((secs("Optimum 11 computed in 1.2µs") - 1.2e-6).abs() < 1e-18);
+        assert!((secs("Optimum 11 computed in 3ns")   - 3e-9).abs()   < 1e-18);
+        assert_eq!(None, parse_computed_in("Optimum 11 computed in 5 minutes"));
+    }
+    #[test]
+    fn summary_line_gives_the_duration() {
+        let log   = "
+[1.5s] Explored 100, LB 0, UB 50, Fringe sz 10
+[2.5s] Final 20, Explored 200
+Optimum 20 computed in 2600ms
+";
+        let trace = Trace::from(log);
+        assert_eq!(2,         trace.lines.len());
+        assert_eq!(Some(2.6), trace.duration());
+    }
+    /// A reader which fails after having returned its content
+    struct FailingReader(&'static [u8]);
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() {
+                Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "disconnected"))
+            } else {
+                let n = self.0.len().min(buf.len());
+                buf[..n].copy_from_slice(&self.0[..n]);
+                self.0 = &self.0[n..];
+                Ok(n)
+            }
+        }
+    }
+    #[test]
+    fn reading_stops_at_the_first_io_error() {
+        let log   = "Explored 100, LB 0, UB 50, Fringe sz 10\nExplored 200, LB 5, UB 40, Fringe sz 20\n";
+        let lines = BufReader::new(FailingReader(log.as_bytes())).lines();
+
+        let truncated = Trace::try_from_lines(lines).unwrap_err();
+        assert_eq!(2, truncated.lines_read);
+        assert_eq!(2, truncated.trace.lines.len());
+        assert_eq!(std::io::ErrorKind::BrokenPipe, truncated.error.kind());
+
+        let partial   = Trace::from(BufReader::new(FailingReader(log.as_bytes())).lines());
+        assert_eq!(2, partial.lines.len());
+    }
+    #[test]
+    fn stats_round_trip_through_yaml() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 20
+Final 10, Explored 300
+");
+        let stats = vec![trace.stats().unwrap()];
+        let yaml  = serde_yaml::to_string(&stats).unwrap();
+        let back  = serde_yaml::from_str::<Vec<TraceStats>>(&yaml).unwrap();
+
+        assert_eq!(1, back.len());
+        assert_eq!(stats[0].name,       back[0].name);
+        assert_eq!(stats[0].explored,   back[0].explored);
+        assert_eq!(stats[0].lb,         back[0].lb);
+        assert_eq!(stats[0].ub,         back[0].ub);
+        assert_eq!(stats[0].gap,        back[0].gap);
+        assert_eq!(stats[0].proven,     back[0].proven);
+        assert_eq!(stats[0].decay_rate, back[0].decay_rate);
+        assert_eq!(stats[0].gap_area,   back[0].gap_area);
+        assert_eq!(stats[0].time,       back[0].time);
+        assert_eq!(stats[0].fringe_growing, back[0].fringe_growing);
+    }
+    #[test]
+    fn first_incumbent_is_the_first_improvement_of_the_lb() {
+        let trace = Trace::from("
+Explored 100, LB -1000, UB 30, Fringe sz 10
+Explored 200, LB -1000, UB 25, Fringe sz 20
+Explored 300, LB 5, UB 20, Fringe sz 20
+Explored 400, LB 8, UB 20, Fringe sz 20
+");
+        assert_eq!(Some(300), trace.first_incumbent());
+        assert_eq!(None,      Trace::from("Explored 100, LB 5, UB 30, Fringe sz 10").first_incumbent());
+        assert_eq!(None,      Trace::from("").first_incumbent());
+    }
+    #[test]
+    fn area_under_gap_uses_the_trapezoidal_rule() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 5, UB 25, Fringe sz 20
+Explored 400, LB 10, UB 20, Fringe sz 20
+Final 15, Explored 500
+");
+        // (100 * (30 + 20) / 2) + (200 * (20 + 10) / 2)
+        assert_eq!(5500.0, trace.area_under_gap());
+        assert_eq!(0.0,    Trace::from("Explored 100, LB 0, UB 30, Fringe sz 10").area_under_gap());
+        assert_eq!(0.0,    Trace::from("").area_under_gap());
+    }
+    #[test]
+    fn push_line_only_appends_log_lines() {
+        let mut trace = Trace::from("");
+
+        assert!( trace.push_line("Explored 100, LB 0, UB 30, Fringe sz 10"));
+        assert!(!trace.push_line("this is not a log line"));
+        assert!(!trace.push_line(""));
+        assert!( trace.push_line("Explored 200, LB 5, UB 20, Fringe sz 20\r"));
+        assert!(!trace.push_line("Explored 300, LB 5"));
+        assert!( trace.push_line("Final 10, Explored 300"));
+
+        assert_eq!(vec![100, 200, 300], trace.lines.iter().map(|ll| ll.explored()).collect::<Vec<_>>());
+        assert!(trace.is_proven());
+    }
+    #[test]
+    fn fringe_ratio_skips_lines_without_explored_nodes() {
+        let trace = Trace::from("
+Explored 0, LB 0, UB 30, Fringe sz 1
+Explored 100, LB 0, UB 30, Fringe sz 50
+Explored 200, LB 5, UB 20, Fringe sz 400
+");
+        assert_eq!(vec![(100.0, 0.5), (200.0, 2.0)], trace.fringe_ratio());
+    }
+    #[test]
+    fn ub_band_aggregates_the_runs_covering_each_count() {
+        let runs = [
+            Trace::from("Explored 100, LB 0, UB 30, Fringe sz 1\nExplored 300, LB 0, UB 10, Fringe sz 1"),
+            Trace::from("Explored 100, LB 0, UB 40, Fringe sz 1\nExplored 300, LB 0, UB 20, Fringe sz 1"),
+            Trace::from("Explored 100, LB 0, UB 50, Fringe sz 1\nExplored 200, LB 0, UB 40, Fringe sz 1"),
+        ];
+        let band = ub_band(&runs, 3);
+
+        assert_eq!(vec![(100.0, 30.0), (200.0, 20.0), (300.0, 10.0)], band.min);
+        assert_eq!(vec![(100.0, 40.0), (200.0, 30.0), (300.0, 15.0)], band.median);
+        assert_eq!(vec![(100.0, 50.0), (200.0, 40.0), (300.0, 20.0)], band.max);
+        assert!(ub_band(&[], 3).median.is_empty());
+    }
+    #[test]
+    fn parse_solution_block() {
+        let trace = Trace::from("
+Final 11, Explored 27966
+Optimum 11 computed in 6.473472s with 1 threads
+### Solution: ################################################
+ 2 25 52 54 70 74 76 132 140 155 164
+");
+        assert_eq!(Some(&[2, 25, 52, 54, 70, 74, 76, 132, 140, 155, 164][..]), trace.solution());
+        assert_eq!(1, trace.lines.len());
+        assert_eq!(None, Trace::from("Final 11, Explored 27966").solution());
+    }
+    #[test]
+    fn over_long_lines_are_skipped() {
+        let long  = format!("Explored 150, LB 1, UB 40, Fringe sz {}", "9".repeat(1000));
+        let log   = format!("Explored 100, LB 0, UB 50, Fringe sz 10\n{}\nExplored 200, LB 5, UB 40, Fringe sz 20\r\n", long);
+        let mut lines = BoundedLines::new(BufReader::with_capacity(16, log.as_bytes()), 64);
+        let trace = Trace::try_from_lines(&mut lines).unwrap();
+
+        assert_eq!(1, lines.skipped);
+        assert_eq!(vec![100, 200], trace.lines.iter().map(|ll| ll.explored()).collect::<Vec<_>>());
+    }
+    #[test]
+    fn bounded_lines_match_the_std_lines() {
+        let log   = "a\n\nb\r\nc";
+        let lines = BoundedLines::new(log.as_bytes(), 64).collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(log.as_bytes().lines().collect::<Result<Vec<_>, _>>().unwrap(), lines);
+    }
+    #[test]
+    fn binned_fringe_averages_each_bin() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 0, UB 30, Fringe sz 30
+Explored 300, LB 0, UB 30, Fringe sz 20
+Explored 900, LB 0, UB 30, Fringe sz 40
+Explored 1000, LB 0, UB 30, Fringe sz 60
+");
+        assert_eq!(vec![(200.0, 20.0), (950.0, 50.0)], trace.binned_fringe(3));
+        assert_eq!(vec![(500.0, 32.0)],                trace.binned_fringe(1));
+        assert!(Trace::from("").binned_fringe(3).is_empty());
+    }
+    #[test]
+    fn gap_velocity_skips_null_explored_deltas() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 40, Fringe sz 10
+Explored 200, LB 10, UB 30, Fringe sz 10
+Explored 200, LB 12, UB 30, Fringe sz 10
+Explored 400, LB 12, UB 14, Fringe sz 10
+");
+        assert_eq!(vec![(200.0, -0.2), (400.0, -0.08)], trace.gap_velocity());
+        assert!(Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10").gap_velocity().is_empty());
+    }
+    #[test]
+    fn smoothed_averages_the_trailing_window() {
+        let points = vec![(1.0, 3.0), (2.0, 1.0), (3.0, 5.0), (4.0, 0.0)];
+
+        assert_eq!(vec![(1.0, 3.0), (2.0, 2.0), (3.0, 3.0), (4.0, 2.5)], smoothed(points.clone(), 2));
+        assert_eq!(points.clone(), smoothed(points, 1));
+    }
+    #[test]
+    fn sentinel_bounds_are_detected() {
+        let trace = Trace::from("
+Explored 100, LB -2147483647, UB 2147483647, Fringe sz 10
+Explored 200, LB -20, UB 2147483647, Fringe sz 30
+Explored 300, LB -12, UB -3, Fringe sz 20
+");
+        assert!(trace.lines[0].has_sentinel_lb());
+        assert!(!trace.lines[1].has_sentinel_lb());
+        assert!(trace.lines[1].has_sentinel_ub());
+        assert_eq!(Some((-20, -3)), trace.clipped_bound_range());
+        assert_eq!(None, Trace::from("Explored 100, LB -2147483647, UB 2147483647, Fringe sz 10").clipped_bound_range());
+    }
+    #[test]
+    fn log_format_registry() {
+        let latest = LogFormat::latest();
+
+        assert_eq!(LATEST_VERSION, latest.version);
+        assert!(LogFormat::versions().contains(&LATEST_VERSION));
+        assert!(LogFormat::of("no-such-version").is_none());
+        assert_eq!(Some(LogLine::Final { explored: 6790, opt_value: 11, segment: 0, time: None }),
+                   latest.parse("Final 11, Explored 6790"));
+        assert_eq!(Some(1.5), latest.parse_computed_in("Optimum computed in 1.5s"));
+
+        let lines = "Explored 100, LB 0, UB 40, Fringe sz 10\nFinal 12, Explored 500".lines().map(|l| Ok(l.to_string()));
+        let options = ReadOptions { format: latest, ..ReadOptions::default() };
+        assert_eq!(2, Trace::try_from_lines_with(lines, &options).unwrap().lines.len());
+    }
+    #[test]
+    fn only_the_selected_lines_are_parsed() {
+        let log = "
+[w0] Explored 100, LB 0, UB 40, Fringe sz 10
+[w1] Explored 150, LB 2, UB 50, Fringe sz 10
+[w0] Explored 200, LB 5, UB 30, Fringe sz 30
+[w1] Final 12, Explored 500
+";
+        let options = ReadOptions { select: Some(Regex::new(r"^\[w0\]").unwrap()), ..ReadOptions::default() };
+        let trace   = Trace::try_from_lines_with(log.lines().map(|l| Ok(l.to_string())), &options).unwrap();
+
+        assert_eq!(vec![100, 200], trace.lines.iter().map(LogLine::explored).collect::<Vec<usize>>());
+        assert_eq!(4, Trace::try_from_lines(log.lines().map(|l| Ok(l.to_string()))).unwrap().lines.len());
+    }
+    #[test]
+    fn parse_stats_of_the_sample_trace() {
+        let lines      = include_str!("../example.trc").lines().map(|l| Ok(l.to_string()));
+        let (_, stats) = Trace::parse_strict(lines, &ReadOptions::default()).unwrap();
+        assert_eq!(ParseStats { ongoing: 279, final_: 1, skipped: 0 }, stats);
+
+        let log        = "Explored 100, LB 0, UB 40, Fringe sz 10\nsome noise\n\n--- restart 1 ---\nFinal 12, Explored 500";
+        let (_, stats) = Trace::parse_strict(log.lines().map(|l| Ok(l.to_string())), &ReadOptions::default()).unwrap();
+        assert_eq!(ParseStats { ongoing: 1, final_: 1, skipped: 1 }, stats);
+    }
+    #[test]
+    fn cumulative_lb_gain_only_adds_the_increases() {
+        let trace = Trace::from("
+Explored 100, LB -2147483647, UB 40, Fringe sz 10
+Explored 200, LB 5, UB 30, Fringe sz 10
+Explored 300, LB 3, UB 30, Fringe sz 10
+Explored 400, LB 10, UB 14, Fringe sz 10
+Final 12, Explored 500
+");
+        assert_eq!(vec![(100.0, 0.0), (200.0, 0.0), (300.0, 0.0), (400.0, 7.0), (500.0, 9.0)],
+                   trace.cumulative_lb_gain());
+        assert!(Trace::from("").cumulative_lb_gain().is_empty());
+    }
+    #[test]
+    fn round_trip_check_reports_the_nonstandard_lines() {
+        let log = "
+[1.5s] Explored 200, LB 5, UB 30, Fringe sz 10
+--- restart 1 ---
+Explored 6,700, LB 8, UB 20, Fringe sz 10
+  \x1b[32mFinal 14, Explored 7000\x1b[0m
+";
+        let (parsed, mismatches) = round_trip_check(log.lines().map(|l| Ok(l.to_string())), &ReadOptions::default()).unwrap();
+
+        assert_eq!(3, parsed);
+        assert_eq!(vec![RoundTripMismatch {
+            line_no: 4,
+            read   : "Explored 6,700, LB 8, UB 20, Fringe sz 10".to_string(),
+            written: "Explored 6700, LB 8, UB 20, Fringe sz 10".to_string()
+        }], mismatches);
+    }
+    #[test]
+    fn crossings_are_the_lines_whose_lb_exceeds_the_ub() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 40, Fringe sz 10
+Explored 200, LB 35, UB 30, Fringe sz 10
+Explored 300, LB 20, UB 20, Fringe sz 10
+Explored 400, LB 18, UB 14, Fringe sz 10
+Final 14, Explored 500
+");
+        let explored = trace.crossings().iter().map(|ll| ll.explored()).collect::<Vec<usize>>();
+        assert_eq!(vec![200, 400], explored);
+        assert!(Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10").crossings().is_empty());
+    }
+    #[test]
+    fn distinct_ub_count_ignores_repeated_and_sentinel_bounds() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 2147483647, Fringe sz 10
+Explored 200, LB 5, UB 30, Fringe sz 10
+Explored 300, LB 5, UB 30, Fringe sz 10
+Explored 400, LB 10, UB 14, Fringe sz 10
+Final 14, Explored 500
+");
+        assert_eq!(vec![(100.0, 0.0), (200.0, 1.0), (300.0, 1.0), (400.0, 2.0), (500.0, 2.0)],
+                   trace.distinct_ub_count());
+        assert!(Trace::from("").distinct_ub_count().is_empty());
+    }
+    #[test]
+    fn explored_to_close_interpolates_the_crossing() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 40, Fringe sz 10
+Explored 200, LB 10, UB 30, Fringe sz 10
+Explored 400, LB 12, UB 14, Fringe sz 10
+Final 12, Explored 500
+");
+        assert_eq!(Some(100.0), trace.explored_to_close(50.0));
+        assert_eq!(Some(300.0), trace.explored_to_close(11.0));
+        assert_eq!(Some(500.0), trace.explored_to_close(0.0));
+        assert_eq!(None, Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10").explored_to_close(0.0));
+    }
+    #[test]
+    fn ansi_color_codes_are_stripped() {
+        let log   = "\x1b[32mExplored 100, LB 0, UB 40, Fringe sz 10\x1b[0m\n\x1b[1;31mFinal 12\x1b[0m, Explored 500";
+        let trace = Trace::try_from_lines(log.lines().map(|l| Ok(l.to_string()))).unwrap();
+
+        assert_eq!("Explored 100, LB 0, UB 40, Fringe sz 10", strip_ansi("\x1b[32mExplored 100, LB 0, UB 40, Fringe sz 10\x1b[0m"));
+        assert_eq!(vec![100, 500], trace.lines.iter().map(LogLine::explored).collect::<Vec<usize>>());
+        assert_eq!(2, Trace::from(log).lines.len());
+    }
+    #[test]
+    fn changes_only_keeps_the_lines_where_a_bound_changed() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 40, Fringe sz 10
+Explored 200, LB 0, UB 40, Fringe sz 30
+Explored 300, LB 5, UB 40, Fringe sz 20
+Explored 400, LB 5, UB 40, Fringe sz 20
+Explored 500, LB 5, UB 30, Fringe sz 5
+Explored 600, LB 5, UB 30, Fringe sz 5
+Explored 700, LB 5, UB 30, Fringe sz 2
+");
+        let thin  = trace.changes_only();
+
+        assert_eq!(vec![100, 300, 500, 700], thin.lines.iter().map(LogLine::explored).collect::<Vec<usize>>());
+        assert_eq!(vec![100], Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10").changes_only()
+            .lines.iter().map(LogLine::explored).collect::<Vec<usize>>());
+        assert!(Trace::from("").changes_only().lines.is_empty());
+    }
+    #[test]
+    fn with_name_renames_the_trace() {
+        let trace = Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10");
+        assert_eq!(None, trace.name());
+
+        let trace = trace.with_name("merged").with_name(String::from("renamed"));
+        assert_eq!(Some("renamed"), trace.name());
+        assert_eq!(1, trace.lines.len());
+    }
+    #[test]
+    fn ndjson_lines_are_deserialized() {
+        let log = r#"
+{"explored": 100, "lb": 0, "ub": 40, "fringe": 10}
+{"explored": 200, "lb": 5, "ub": 30, "fringe": 30}
+Explored 300, LB 8, UB 20, Fringe sz 20
+"#;
+        let options        = ReadOptions { ndjson: true, ..ReadOptions::default() };
+        let (trace, stats) = Trace::parse_strict(log.lines().map(|l| Ok(l.to_string())), &options).unwrap();
+
+        assert_eq!(LogLine::Ongoing { explored: 200, lb: 5, ub: 30, fringe: 30, segment: 0, time: None }, trace.lines[1]);
+        assert_eq!(ParseStats { ongoing: 2, final_: 0, skipped: 1 }, stats);
+    }
+    #[test]
+    fn a_truncated_file_yields_a_partial_trace() {
+        let path = std::env::temp_dir().join("ddo-plotter-truncated-test.trc");
+        let mut log = b"Explored 100, LB 0, UB 40, Fringe sz 10\n".to_vec();
+        log.extend_from_slice(b"Explored \xff\xfe, LB 5, UB 30, Fringe sz 10\nFinal 12, Explored 500\n");
+        std::fs::write(&path, log).unwrap();
+        let (trace, _) = Trace::load(&path, &ReadOptions::default()).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(vec![100], trace.lines.iter().map(LogLine::explored).collect::<Vec<usize>>());
+    }
+    #[test]
+    fn ndjson_extension_is_detected() {
+        let path = std::env::temp_dir().join("ddo-plotter-ndjson-test.ndjson");
+        std::fs::write(&path, "{\"explored\": 100, \"lb\": 0, \"ub\": 40, \"fringe\": 10}\n").unwrap();
+        let (trace, _) = Trace::load(&path, &ReadOptions::default()).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(Some("ddo-plotter-ndjson-test"), trace.name());
+        assert_eq!(1, trace.lines.len());
+    }
+    #[test]
+    fn csv_columns_extract_the_line_values() {
+        let line    = LogLine::try_from("Explored 100, LB 5, UB 40, Fringe sz 10").unwrap();
+        let columns = CsvColumn::parse_list("explored, gap,ub,time").unwrap();
+
+        assert_eq!(vec![CsvColumn::Explored, CsvColumn::Gap, CsvColumn::Ub, CsvColumn::Time], columns);
+        assert_eq!(vec!["100", "35", "40", ""], columns.iter().map(|c| c.value(&line)).collect::<Vec<_>>());
+        assert_eq!(vec!["explored", "lb", "ub", "fringe"], DEFAULT_CSV_COLUMNS.iter().map(|c| c.name()).collect::<Vec<_>>());
+        assert!(CsvColumn::parse_list("explored,bogus").unwrap_err().contains("'gap'"));
+    }
+    #[test]
+    fn counts_with_thousands_separators() {
+        let ongoing = LogLine::try_from("Explored 6,700, LB 5, UB 40, Fringe sz 1,234,567").unwrap();
+        let last    = LogLine::try_from("Final 11, Explored 1,234,567").unwrap();
+
+        assert_eq!((6700, 1234567), (ongoing.explored(), ongoing.fringe()));
+        assert_eq!((5, 40), (ongoing.lb(), ongoing.ub()));
+        assert_eq!((1234567, 11), (last.explored(), last.lb()));
+        assert_eq!(6700, LogLine::try_from("Explored 6700, LB 5, UB 40, Fringe sz 10").unwrap().explored());
+        assert!(LogLine::try_from("Explored 6,70, LB 5, UB 40, Fringe sz 10").is_err());
+    }
+    #[test]
+    fn counts_too_large_for_an_usize_are_skipped() {
+        assert!(LogLine::try_from("Explored 99,999,999,999,999,999,999, LB 5, UB 40, Fringe sz 10").is_err());
+        assert!(LogLine::try_from("Final 11, Explored 99,999,999,999,999,999,999").is_err());
+
+        let log = "Explored 100, LB 0, UB 40, Fringe sz 99,999,999,999,999,999,999
+Explored 200, LB 5, UB 30, Fringe sz 10";
+        let (trace, stats) = Trace::parse_strict(log.lines().map(|l| Ok(l.to_string())), &ReadOptions::default()).unwrap();
+        assert_eq!(vec![200], trace.lines.iter().map(LogLine::explored).collect::<Vec<_>>());
+        assert_eq!((1, 1), (stats.ongoing, stats.skipped));
+    }
+    #[test]
+    fn final_line_before_the_ongoing_lines() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 40, Fringe sz 10
+Final 12, Explored 500
+Explored 200, LB 5, UB 30, Fringe sz 30
+Explored 400, LB 10, UB 15, Fringe sz 5
+");
+        let fixed = trace.with_final_last();
+
+        assert!(trace.has_out_of_order_final());
+        assert!(!fixed.has_out_of_order_final());
+        assert_eq!(vec![100, 200, 400, 500], fixed.lines.iter().map(LogLine::explored).collect::<Vec<usize>>());
+        assert!(!Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10\nFinal 12, Explored 500").has_out_of_order_final());
+    }
+    #[test]
+    fn time_window_of_a_timestamped_trace() {
+        let trace = Trace::from("
+[0.5s] Explored 100, LB 0, UB 40, Fringe sz 10
+[1.0s] Explored 200, LB 5, UB 30, Fringe sz 30
+[2.5s] Explored 300, LB 8, UB 20, Fringe sz 20
+[4.0s] Final 12, Explored 500
+");
+        let explored = |t: Trace| t.lines.iter().map(LogLine::explored).collect::<Vec<usize>>();
+
+        assert_eq!(vec![200, 300], explored(trace.window_time(Some(1.0), Some(2.5))));
+        assert_eq!(vec![300, 500], explored(trace.window_time(Some(2.0), None)));
+        assert_eq!(vec![100], explored(trace.window_time(None, Some(0.9))));
+        assert!(Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10").window_time(None, None).lines.is_empty());
+    }
+    #[test]
+    fn explored_range_of_a_trace() {
+        let trace = Trace::from("
+Explored 300, LB 0, UB 40, Fringe sz 10
+Explored 100, LB 5, UB 30, Fringe sz 30
+Final 12, Explored 500
+");
+        assert_eq!(Some((100, 500)), trace.explored_range());
+        assert_eq!(Some((100, 100)), Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10").explored_range());
+        assert_eq!(None, Trace::from("").explored_range());
+    }
+    #[test]
+    fn fraction_of_the_steps_where_the_fringe_grew() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 40, Fringe sz 10
+Explored 200, LB 5, UB 30, Fringe sz 30
+Explored 300, LB 8, UB 20, Fringe sz 40
+Explored 400, LB 10, UB 15, Fringe sz 5
+Explored 450, LB 10, UB 15, Fringe sz 5
+Final 12, Explored 500
+");
+        assert_eq!(0.5, trace.fringe_growing_fraction());
+        assert_eq!(0.5, trace.stats().unwrap().fringe_growing);
+        assert_eq!(0.0, Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10").fringe_growing_fraction());
+    }
 }
\ No newline at end of file
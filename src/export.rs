@@ -0,0 +1,243 @@
+use std::fs::write;
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use plotlib::page::Page;
+use plotlib::view::View;
+use resvg::{tiny_skia, usvg};
+
+use crate::config::Dimension;
+
+/// The prefix of the data uri of a base64 encoded png image
+pub const PNG_DATA_URI: &str = "data:image/png;base64,";
+/// plotlib's default page size. This is also the size of a cell of a grid of
+/// small multiples (unless the dimension of the whole grid is given).
+pub const PAGE_WIDTH : u32 = 600;
+pub const PAGE_HEIGHT: u32 = 400;
+
+/// Lays the views out on one page. When a dimension is given, it is the size
+/// of the page whatever the output (text, svg or png); otherwise plotlib's
+/// default size is used.
+pub fn page<V: View>(views: &[V], dimension: Option<Dimension>) -> Page<'_> {
+    let page = views.iter().fold(Page::empty(), |page, view| page.add_plot(view as &dyn View));
+    match dimension {
+        Some(dim) => page.dimensions(dim.x(), dim.y()),
+        None      => page
+    }
+}
+
+/// Renders the page to svg. Because plotlib has no notion of a title, the
+/// title (if any) is written in the top margin of the page.
+pub fn to_svg(page: &Page, title: Option<&str>) -> Result<String, String> {
+    let svg = page.to_svg().map_err(|e| e.to_string())?.to_string();
+    Ok(match title {
+        Some(title) => with_title(svg, title),
+        None        => svg
+    })
+}
+
+/// The number of columns of an approximately square grid of `n` cells
+pub fn grid_columns(n: usize) -> usize {
+    (n as f64).sqrt().ceil().max(1.0) as usize
+}
+
+/// Renders the views as small multiples: each view is drawn in a cell of its
+/// own, the cells being laid out in an approximately square grid. When a
+/// dimension is given, it is the size of the whole grid. When `square` is set,
+/// the cells are the largest squares fitting in that grid. Because plotlib
+/// draws all the views of a page on top of each other, each cell is rendered on
+/// a page of its own which is then nested in the svg of the grid.
+pub fn grid_to_svg<V: View>(views: &[V], dimension: Option<Dimension>, square: bool, title: Option<&str>) -> Result<String, String> {
+    let cols = grid_columns(views.len());
+    let rows = views.len().div_ceil(cols).max(1);
+    let (width, height) = match dimension {
+        Some(dim) => (dim.x(), dim.y()),
+        None      => (cols as u32 * PAGE_WIDTH, rows as u32 * PAGE_HEIGHT)
+    };
+    let (cell_w, cell_h) = match (width / cols as u32, height / rows as u32) {
+        (w, h) if square => (w.min(h), h.min(w)),
+        (w, h)           => (w, h)
+    };
+    let (width, height)  = (cols as u32 * cell_w, rows as u32 * cell_h);
+
+    let mut svg = format!("<svg viewBox=\"0 0 {} {}\" xmlns=\"http://www.w3.org/2000/svg\">", width, height);
+    for (i, view) in views.iter().enumerate() {
+        let cell = Page::single(view as &dyn View).dimensions(cell_w, cell_h).to_svg().map_err(|e| e.to_string())?;
+        let (x, y) = ((i % cols) as u32 * cell_w, (i / cols) as u32 * cell_h);
+        svg += &nested(cell.to_string(), x, y, cell_w, cell_h);
+    }
+    svg += "</svg>";
+    Ok(match title {
+        Some(title) => with_title(svg, title),
+        None        => svg
+    })
+}
+
+/// Places the svg document in the given box of an enclosing document
+fn nested(svg: String, x: u32, y: u32, width: u32, height: u32) -> String {
+    match svg.find("<svg") {
+        Some(pos) => format!("<svg x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}",
+                             x, y, width, height, &svg[pos + "<svg".len()..]),
+        None      => svg
+    }
+}
+
+/// Renders the page to a png image. Because plotlib only knows how to draw
+/// svg, the svg rendering of the page is rasterized (on a white background).
+pub fn to_png(page: &Page, title: Option<&str>) -> Result<Vec<u8>, String> {
+    rasterize(&to_svg(page, title)?)
+}
+
+/// Rasterizes the svg document to a png image (on a white background)
+fn rasterize(svg: &str) -> Result<Vec<u8>, String> {
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|e| e.to_string())?;
+
+    let size       = tree.size().to_int_size();
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or("Cannot allocate a png image of the size of the page")?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+/// Renders the page to a png image encoded as a base64 data uri which can
+/// be embedded as is in a notebook or a markdown document.
+pub fn to_png_base64(page: &Page, title: Option<&str>) -> Result<String, String> {
+    svg_to_png_base64(&to_svg(page, title)?)
+}
+
+/// Rasterizes the svg document to a png image encoded as a base64 data uri
+pub fn svg_to_png_base64(svg: &str) -> Result<String, String> {
+    let png = rasterize(svg)?;
+    Ok(format!("{}{}", PNG_DATA_URI, STANDARD.encode(png)))
+}
+
+/// Saves the page to the given path, in the format given by the extension
+/// of that path: either svg or png.
+pub fn save(page: &Page, title: Option<&str>, path: &Path) -> Result<(), String> {
+    save_svg(to_svg(page, title)?, path)
+}
+
+/// Saves the svg document to the given path, either as is or rasterized to
+/// png depending on the extension of that path.
+pub fn save_svg(svg: String, path: &Path) -> Result<(), String> {
+    let content = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("svg") => svg.into_bytes(),
+        Some("png") => rasterize(&svg)?,
+        _           => return Err("Unsupported output format (expected .svg or .png)".to_string())
+    };
+    write(path, content).map_err(|e| e.to_string())
+}
+
+/// Inserts the title as the first element of the svg document, centered in
+/// the top margin of the page.
+fn with_title(svg: String, title: &str) -> String {
+    let start = svg.find("<svg").and_then(|s| svg[s..].find('>').map(|e| s + e + 1));
+    match start {
+        Some(pos) => {
+            let title = title.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+            format!("{}<text x=\"50%\" y=\"20\" text-anchor=\"middle\" font-size=\"16\">{}</text>{}",
+                    &svg[..pos], title, &svg[pos..])
+        },
+        None => svg
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use plotlib::page::Page;
+
+    use crate::config::Dimension;
+    use crate::data::Trace;
+    use crate::export::{grid_columns, grid_to_svg, page, save, to_png_base64, to_svg, with_title, PNG_DATA_URI};
+    use crate::repr::{bounds_view, ViewOptions};
+
+    #[test]
+    fn png_base64_is_a_data_uri_of_a_png_image() {
+        let trace = Trace::from("
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 20
+Final 10, Explored 300
+");
+        let view  = bounds_view(&[trace], &ViewOptions::default());
+        let uri   = to_png_base64(&Page::single(&view), None).unwrap();
+
+        assert!(uri.starts_with(PNG_DATA_URI));
+        let png   = STANDARD.decode(&uri[PNG_DATA_URI.len()..]).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+    #[test]
+    fn title_is_the_first_element_of_the_svg() {
+        let svg = "<?xml version=\"1.0\"?><svg viewBox=\"0 0 600 400\"><g></g></svg>".to_string();
+
+        assert_eq!("<?xml version=\"1.0\"?><svg viewBox=\"0 0 600 400\">\
+                    <text x=\"50%\" y=\"20\" text-anchor=\"middle\" font-size=\"16\">a &lt;b&gt; &amp; c</text>\
+                    <g></g></svg>",
+                   with_title(svg, "a <b> & c"));
+    }
+    #[test]
+    fn save_picks_the_format_from_the_extension() {
+        let view = bounds_view(&[Trace::from("Explored 100, LB 0, UB 30, Fringe sz 10")], &ViewOptions::default());
+        let page = Page::single(&view);
+        let dir  = std::env::temp_dir();
+
+        let svg  = dir.join("ddo-plotter-save-test.svg");
+        let png  = dir.join("ddo-plotter-save-test.png");
+        save(&page, None, &svg).unwrap();
+        save(&page, None, &png).unwrap();
+        assert!(std::fs::read_to_string(&svg).unwrap().contains("<svg"));
+        assert!(std::fs::read(&png).unwrap().starts_with(b"\x89PNG"));
+        assert!(save(&page, None, &dir.join("ddo-plotter-save-test.pdf")).is_err());
+
+        let _ = std::fs::remove_file(svg);
+        let _ = std::fs::remove_file(png);
+    }
+    #[test]
+    fn save_to_an_unwritable_path_reports_the_os_error() {
+        let view = bounds_view(&[Trace::from("Explored 100, LB 0, UB 30, Fringe sz 10")], &ViewOptions::default());
+        let page = Page::single(&view);
+        let path = std::env::temp_dir().join("ddo-plotter-missing-dir").join("graph.svg");
+
+        let error = save(&page, None, &path).unwrap_err();
+        assert!(error.contains("os error"), "{}", error);
+    }
+    #[test]
+    fn svg_honors_the_dimension() {
+        let views = vec![bounds_view(&[Trace::from("Explored 100, LB 0, UB 30, Fringe sz 10")], &ViewOptions::default())];
+        let dim   = "300,200".parse::<Dimension>().unwrap();
+
+        assert!(to_svg(&page(&views, Some(dim)), None).unwrap().contains("viewBox=\"0 0 300 200\""));
+        assert!(!to_svg(&page(&views, None), None).unwrap().contains("viewBox=\"0 0 300 200\""));
+    }
+    #[test]
+    fn small_multiples_are_laid_out_in_a_square_grid() {
+        let views = (1..=5)
+            .map(|i| bounds_view(&[Trace::from(format!("Explored {}, LB 0, UB 30, Fringe sz 10", i * 100).as_str())], &ViewOptions::default()))
+            .collect::<Vec<_>>();
+        let svg   = grid_to_svg(&views, None, false, Some("grid")).unwrap();
+
+        assert_eq!(vec![1, 1, 2, 2, 2, 3], (0..=5).map(grid_columns).collect::<Vec<_>>());
+        assert!(svg.starts_with("<svg viewBox=\"0 0 1800 800\""));
+        assert_eq!(5, svg.matches("width=\"600\" height=\"400\"").count());
+        assert!(svg.contains("<svg x=\"600\" y=\"400\""));
+        assert!(svg.contains(">grid</text>"));
+    }
+    #[test]
+    fn small_multiples_in_square_cells() {
+        let views = (1..=3)
+            .map(|i| bounds_view(&[Trace::from(format!("Explored {}, LB 0, UB 30, Fringe sz 10", i * 100).as_str())], &ViewOptions::default()))
+            .collect::<Vec<_>>();
+        let dim   = "900,900".parse::<Dimension>().unwrap();
+        let svg   = grid_to_svg(&views, Some(dim), true, None).unwrap();
+
+        assert!(svg.starts_with("<svg viewBox=\"0 0 900 900\""));
+        assert_eq!(3, svg.matches("width=\"450\" height=\"450\"").count());
+        assert!(grid_to_svg(&views, None, true, None).unwrap().starts_with("<svg viewBox=\"0 0 800 800\""));
+    }
+}
\ No newline at end of file
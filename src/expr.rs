@@ -0,0 +1,221 @@
+use std::fmt;
+use std::str::FromStr;
+
+// --------------------------------------------------------------------------- //
+/// A (tiny) arithmetic expression of the explored count `x`, such as
+/// `2 * sqrt(x) + log(x + 1)`. It supports the `+ - * /` operators, the
+/// `log` (natural logarithm) and `sqrt` functions, numbers and parentheses.
+// --------------------------------------------------------------------------- //
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expr {
+    /// The text the expression was parsed from
+    text: String,
+    /// The root of the syntax tree
+    root: Node
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Num(f64),
+    X,
+    Neg(Box<Node>),
+    Add(Box<Node>, Box<Node>),
+    Sub(Box<Node>, Box<Node>),
+    Mul(Box<Node>, Box<Node>),
+    Div(Box<Node>, Box<Node>),
+    Log(Box<Node>),
+    Sqrt(Box<Node>)
+}
+
+impl Expr {
+    /// The value of the expression at `x`. This is NaN (or infinite) wherever
+    /// the expression is undefined, e.g. `log(x)` at zero.
+    pub fn eval(&self, x: f64) -> f64 {
+        self.root.eval(x)
+    }
+    /// Evaluates the expression at `samples` evenly spaced values of `x`
+    /// spanning the given range. The points where it is undefined are dropped.
+    pub fn sample(&self, (min, max): (f64, f64), samples: usize) -> Vec<(f64, f64)> {
+        let step = if samples > 1 { (max - min) / (samples - 1) as f64 } else { 0.0 };
+        (0..samples)
+            .map(|i| min + i as f64 * step)
+            .map(|x| (x, self.eval(x)))
+            .filter(|(_, y)| y.is_finite())
+            .collect()
+    }
+}
+
+impl Node {
+    fn eval(&self, x: f64) -> f64 {
+        match self {
+            Node::Num(v)    => *v,
+            Node::X         => x,
+            Node::Neg(a)    => -a.eval(x),
+            Node::Add(a, b) => a.eval(x) + b.eval(x),
+            Node::Sub(a, b) => a.eval(x) - b.eval(x),
+            Node::Mul(a, b) => a.eval(x) * b.eval(x),
+            Node::Div(a, b) => a.eval(x) / b.eval(x),
+            Node::Log(a)    => a.eval(x).ln(),
+            Node::Sqrt(a)   => a.eval(x).sqrt()
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text.trim())
+    }
+}
+
+impl FromStr for Expr {
+    type Err = &'static str;
+    fn from_str(txt: &str) -> Result<Expr, Self::Err> {
+        let mut parser = Parser { chars: txt.chars().collect(), pos: 0 };
+        let root       = parser.expr()?;
+        parser.skip_spaces();
+        if parser.pos < parser.chars.len() {
+            return Err("Unexpected character in the expression");
+        }
+        Ok(Expr { text: txt.to_string(), root })
+    }
+}
+
+// --------------------------------------------------------------------------- //
+// Parsing d'une expression (descente récursive)
+//
+//   expr   := term   (('+' | '-') term)*
+//   term   := factor (('*' | '/') factor)*
+//   factor := '-' factor | number | 'x' | func '(' expr ')' | '(' expr ')'
+//   func   := 'log' | 'sqrt'
+// --------------------------------------------------------------------------- //
+struct Parser {
+    chars: Vec<char>,
+    pos  : usize
+}
+
+impl Parser {
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+    /// Skips the blanks and returns the next character (without consuming it)
+    fn peek(&mut self) -> Option<char> {
+        self.skip_spaces();
+        self.chars.get(self.pos).copied()
+    }
+    fn expect(&mut self, c: char) -> Result<(), &'static str> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err("Unbalanced parentheses in the expression")
+        }
+    }
+    fn expr(&mut self) -> Result<Node, &'static str> {
+        let mut node = self.term()?;
+        loop {
+            match self.peek() {
+                Some('+') => { self.pos += 1; node = Node::Add(Box::new(node), Box::new(self.term()?)); },
+                Some('-') => { self.pos += 1; node = Node::Sub(Box::new(node), Box::new(self.term()?)); },
+                _         => return Ok(node)
+            }
+        }
+    }
+    fn term(&mut self) -> Result<Node, &'static str> {
+        let mut node = self.factor()?;
+        loop {
+            match self.peek() {
+                Some('*') => { self.pos += 1; node = Node::Mul(Box::new(node), Box::new(self.factor()?)); },
+                Some('/') => { self.pos += 1; node = Node::Div(Box::new(node), Box::new(self.factor()?)); },
+                _         => return Ok(node)
+            }
+        }
+    }
+    fn factor(&mut self) -> Result<Node, &'static str> {
+        match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(Node::Neg(Box::new(self.factor()?)))
+            },
+            Some('(') => {
+                self.pos += 1;
+                let node = self.expr()?;
+                self.expect(')')?;
+                Ok(node)
+            },
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos;
+                while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit() || *c == '.') {
+                    self.pos += 1;
+                }
+                let number = self.chars[start..self.pos].iter().collect::<String>();
+                number.parse().map(Node::Num).map_err(|_| "Invalid number in the expression")
+            },
+            Some(c) if c.is_ascii_alphabetic() => {
+                let start = self.pos;
+                while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_alphabetic()) {
+                    self.pos += 1;
+                }
+                let name = self.chars[start..self.pos].iter().collect::<String>();
+                match name.as_str() {
+                    "x"    => Ok(Node::X),
+                    "log"  => Ok(Node::Log(Box::new(self.argument()?))),
+                    "sqrt" => Ok(Node::Sqrt(Box::new(self.argument()?))),
+                    _      => Err("Unknown name in the expression (expected 'x', 'log' or 'sqrt')")
+                }
+            },
+            _ => Err("Incomplete expression")
+        }
+    }
+    /// Parses the parenthesized argument of a function
+    fn argument(&mut self) -> Result<Node, &'static str> {
+        self.expect('(')?;
+        let node = self.expr()?;
+        self.expect(')')?;
+        Ok(node)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::expr::Expr;
+
+    fn eval(txt: &str, x: f64) -> f64 {
+        txt.parse::<Expr>().unwrap().eval(x)
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(7.0,  eval("1 + 2 * 3", 0.0));
+        assert_eq!(9.0,  eval("(1 + 2) * 3", 0.0));
+        assert_eq!(-4.0, eval("1 - 2 - 3", 0.0));
+        assert_eq!(1.0,  eval("8 / 4 / 2", 0.0));
+        assert_eq!(-6.0, eval("-x * 2", 3.0));
+    }
+    #[test]
+    fn functions_of_x() {
+        assert_eq!(3.0, eval("sqrt(x)", 9.0));
+        assert_eq!(0.0, eval("log(x)", 1.0));
+        assert_eq!(6.0, eval("2 * sqrt(x + 7) - log(1)", 2.0));
+        assert!(eval("log(x)", 0.0).is_infinite());
+    }
+    #[test]
+    fn invalid_expressions() {
+        assert!("".parse::<Expr>().is_err());
+        assert!("1 +".parse::<Expr>().is_err());
+        assert!("(x".parse::<Expr>().is_err());
+        assert!("x)".parse::<Expr>().is_err());
+        assert!("exp(x)".parse::<Expr>().is_err());
+        assert!("sqrt x".parse::<Expr>().is_err());
+        assert!("1.2.3".parse::<Expr>().is_err());
+    }
+    #[test]
+    fn sample_drops_undefined_points() {
+        let expr = "log(x)".parse::<Expr>().unwrap();
+        let pts  = expr.sample((0.0, 2.0), 3);
+
+        assert_eq!(vec![(1.0, 0.0), (2.0, 2.0_f64.ln())], pts);
+        assert_eq!("log(x)", expr.to_string());
+    }
+}
\ No newline at end of file
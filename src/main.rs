@@ -17,72 +17,1498 @@
 // IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
 // CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+extern crate env_logger;
 #[macro_use]
-extern crate lazy_static;
-extern crate regex;
+extern crate log;
+extern crate plotter;
+extern crate serde_json;
+extern crate serde_yaml;
 extern crate structopt;
 
-use std::convert::TryFrom;
-use std::path::Path;
+use std::collections::HashSet;
+use std::fs::{create_dir_all, read_to_string, write, File};
+use std::path::{Path, PathBuf};
+use std::process::exit;
 
-use plotlib::page::Page;
+use log::LevelFilter;
+use regex::Regex;
 use structopt::StructOpt;
 
-use crate::data::Trace;
-use crate::repr::{bounds_view, fringe_view};
-use std::io::{BufReader, BufRead, stdin};
-use crate::config::Dimension;
-
-mod config;
-mod data;
-mod repr;
+use plotter::data::{round_trip_check, BoundedLines, CsvColumn, LogFormat, LogLine, Overlay, ReadOptions, Trace, DEFAULT_CSV_COLUMNS, FRINGE_GROWING_THRESHOLD, MAX_LINE_LENGTH};
+use plotlib::view::{ContinuousView, View};
+use plotter::export::{grid_to_svg, page, save, save_svg, svg_to_png_base64, to_png_base64, to_svg, PAGE_HEIGHT, PAGE_WIDTH};
+use plotter::expr::Expr;
+use plotter::repr::{baseline_view, bounds_and_gap_view, bounds_view, final_metric, final_view, fringe_ratio_view, fringe_view, gap_view, lb_gain_view, min_max_view, relative_gap_view, sparkline, trace_name, ub_progress_view, velocity_view, ViewOptions};
+use std::io::{stdin, stdout, BufRead, BufReader, IsTerminal};
+use plotter::config::{Aggregate, Bound, Defaults, Dimension, FinalMetric, FinalStyle, Format, GapMode, GridLines, LineKind, LogBase, SeriesColor, XField, DEFAULT_CONFIG_FILE};
 
 /// Parse a DDO trace and process it to produce graphs.
 #[derive(StructOpt)]
 struct Args {
-    /// If set, the path to a file containg the text of a ddo trace
+    /// If set, the path to a file containg the text of a ddo trace. The path
+    /// `-` stands for the standard input, e.g. to overlay a live trace with
+    /// saved ones: `solver | plotter -i - -i baseline.log`.
     #[structopt(name="input", short, long)]
     input: Option<Vec<String>>,
-    /// If set, the graph will be saved in svg at the specified location.
-    #[structopt(name="output", short, long)]
-    output: Option<String>,
-    /// If set, the dimension of the terminal (otherwise it will attempt to auto detect)
+    /// If set, the path to a file listing the paths of the traces to plot
+    /// (one per line). Blank lines and lines starting with `#` are ignored.
+    /// The listed traces are plotted along with those given with `--input`.
+    #[structopt(name="input-list", long)]
+    input_list: Option<String>,
+    /// If set, the graph will be saved at the specified location, either in
+    /// svg or in png depending on its extension. The location may contain a
+    /// `{name}` placeholder which is replaced by the name of the plotted trace
+    /// (e.g. `out/{name}.svg`), unless several traces are overlaid. This option
+    /// can be repeated to save the graph in several formats at once. When the
+    /// graph cannot be saved, the program exits with status 2.
+    #[structopt(name="output", short, long, number_of_values=1)]
+    output: Vec<String>,
+    /// If set, the svg markup of the graph is written to the standard output
+    /// (e.g. to pipe it to an svg optimizer) instead of the text graph.
+    /// Nothing else is written to the standard output in this mode.
+    #[structopt(name="svg-stdout", long)]
+    svg_stdout : bool,
+    /// If set, the dimension of the graph: in characters for the terminal, in
+    /// pixels for the svg and png outputs (otherwise a default size is used).
+    /// Either `width,height`, `width` alone or `,height` alone. The text output
+    /// otherwise fits the terminal or, when there is none, follows the
+    /// `DDO_PLOTTER_DIM` environment variable (in the same format).
     #[structopt(name="dimension", short, long)]
     dimension  : Option<Dimension>,
     /// If set, prints the evolution of the fringe size
     #[structopt(name="fringe", short, long)]
     fringe     : bool,
+    /// If set, prints the evolution of the largest fringe size reached so far
+    /// (its high-water mark) rather than that of the fringe size
+    #[structopt(name="fringe-highwater", long)]
+    fringe_highwater: bool,
+    /// If set, prints the evolution of the ratio of the fringe size to the
+    /// explored count (a rising ratio means the search broadens faster than
+    /// it closes)
+    #[structopt(name="fringe-ratio", long)]
+    fringe_ratio: bool,
+    /// If set, prints the rate at which the gap closes (the derivative of the
+    /// gap with respect to the explored count), which reveals the stalls. The
+    /// value (if any) is the number of points of the moving average used to
+    /// smooth it.
+    #[structopt(name="velocity", long)]
+    velocity   : Option<Option<usize>>,
+    /// If set, prints the total improvement of the lower bound accumulated
+    /// over the run (which shows how much progress was made and when)
+    #[structopt(name="lb-gain", long)]
+    lb_gain    : bool,
+    /// If set, prints the number of distinct upper bounds seen so far (a
+    /// staircase whose flat regions reveal the stalls of the upper bound)
+    #[structopt(name="ub-progress", long)]
+    ub_progress: bool,
+    /// If set, the fringe is drawn as the line of its mean size over this
+    /// many bins of explored counts (rather than as a scatter of all points)
+    #[structopt(name="bin-x", long)]
+    bin_x      : Option<usize>,
+    /// If set, the bounds legends also mention the final gap and node count
+    #[structopt(name="rich-legend", long)]
+    rich_legend: bool,
+    /// If set, the bounds legends are kept short even though the
+    /// configuration file asks for rich legends
+    #[structopt(name="no-rich-legend", long, conflicts_with="rich-legend")]
+    no_rich_legend: bool,
+    /// If set, the traces are considered to be logged by several threads whose
+    /// lines are interleaved. They are cleaned up to only report the largest
+    /// explored count and the tightest bounds seen so far.
+    #[structopt(name="untangle", long)]
+    untangle   : bool,
+    /// If set, the `Final` line of a trace which precedes some ongoing lines
+    /// (e.g. in concatenated or reordered logs) is moved after them. Otherwise
+    /// such a trace is only reported.
+    #[structopt(name="sort", long)]
+    sort       : bool,
+    /// If set, only the log lines of this kind (either `ongoing` or `final`)
+    /// are retained, e.g. to get rid of the `Final` lines of a file which
+    /// concatenates several traces.
+    #[structopt(name="only-lines", long)]
+    only_lines : Option<LineKind>,
+    /// If set, the number of (warm-up) log lines to discard from each trace
+    #[structopt(name="skip", long)]
+    skip       : Option<usize>,
+    /// If set, the version of ddo which wrote the traces, so that their lines
+    /// are recognized in the wording of that version (the latest one unless
+    /// specified otherwise)
+    #[structopt(name="ddo-version", long)]
+    ddo_version: Option<String>,
+    /// If set, only the lines of the input matching this regular expression
+    /// are parsed, e.g. `'^\[w0\]'` to keep the lines of one worker of a
+    /// multi-threaded solver (along with `--name`, this splits such a log in
+    /// one trace per worker across several invocations)
+    #[structopt(name="select-lines", long)]
+    select_lines: Option<Regex>,
+    /// If set, the name of the plotted trace (rather than its file stem), e.g.
+    /// the worker whose lines were kept by `--select-lines`. This requires a
+    /// single input.
+    #[structopt(name="name", long)]
+    name       : Option<String>,
+    /// If set, each line of the input is a json object such as
+    /// `{"explored": 100, "lb": 0, "ub": 40, "fringe": 10}` rather than a ddo
+    /// log line. This is implied for the files with an `.ndjson` extension.
+    #[structopt(name="ndjson", long)]
+    ndjson     : bool,
+    /// If set, the length (in bytes) above which a line of the input is
+    /// skipped rather than read (64 KiB unless specified otherwise). This
+    /// guards against malformed logs made of one huge line.
+    #[structopt(name="max-line-length", long)]
+    max_line_length: Option<usize>,
+    /// If set, each trace is plotted on its own instead of being overlaid
+    #[structopt(name="separate", long)]
+    separate   : bool,
+    /// If set, each trace is plotted in a small chart of its own (ranged
+    /// independently of the others) and these charts are laid out in an
+    /// approximately square grid
+    #[structopt(name="small-multiples", long)]
+    small_multiples: bool,
+    /// If set, the graph (or each chart of the small multiples) is drawn in
+    /// the largest square fitting in its dimension
+    #[structopt(name="square", long)]
+    square     : bool,
+    /// If set, prints summary statistics about each trace instead of a graph
+    #[structopt(name="stats", long)]
+    stats      : bool,
+    /// If set, the size of the markers used to plot the points (3 unless
+    /// specified otherwise). This must be positive.
+    #[structopt(name="marker-size", long)]
+    marker_size: Option<f32>,
+    /// If set, the `Final` lines are not drawn in the series (where they make
+    /// the upper bound collapse at the last explored count). They are still
+    /// accounted for in the statistics, legends and reference lines.
+    #[structopt(name="no-final-in-series", long)]
+    no_final_in_series: bool,
+    /// If set, the portion of each series which precedes the first incumbent
+    /// (the search for a first solution) is drawn in a muted shade, apart
+    /// from the portion where optimality is being proven.
+    #[structopt(name="two-phase", long)]
+    two_phase  : bool,
+    /// If set, the bounds are drawn as staircase lines since they remain
+    /// constant between two log lines (rather than changing continuously)
+    #[structopt(name="step", long)]
+    step       : bool,
+    /// If set, the number of ticks on the x-axis. The axis is then extended
+    /// to the nearest round values so that the ticks fall on round counts.
+    #[structopt(name="x-ticks", long)]
+    x_ticks    : Option<usize>,
+    /// The quantity on the x-axis of the bounds plot: either `explored` (the
+    /// default), `fringe`, which plots the bounds against the fringe size
+    /// (a phase-space view of the search), `index`, which plots them
+    /// against the ordinal of each line (for logs whose explored counts are
+    /// missing or always zero), or `time`, which plots them against the
+    /// timestamps of the lines. This can also be given as `--x-axis`. The
+    /// other views are always plotted against the explored count.
+    #[structopt(name="x-field", long, alias="x-axis")]
+    x_field    : Option<XField>,
+    /// If set, the time axis counts the seconds elapsed since the first
+    /// timestamp of each trace, so that runs started at different times are
+    /// aligned. This implies `--x-field time`.
+    #[structopt(name="relative-time", long)]
+    relative_time: bool,
+    /// If set, each legend entry also mentions the number of points of the series
+    #[structopt(name="legend-counts", long)]
+    legend_counts: bool,
+    /// If set, only prints the headline numbers of each trace (explored
+    /// count, final bounds, gap, convergence and solve time) without plotting
+    #[structopt(name="summary-only", long)]
+    summary_only: bool,
+    /// If set, the segments delimited by restart markers are drawn in different shades
+    #[structopt(name="segments", long)]
+    segments   : bool,
+    /// If set, the segments are drawn in the same shade even though the
+    /// configuration file asks for shaded segments
+    #[structopt(name="no-segments", long, conflicts_with="segments")]
+    no_segments: bool,
+    /// The maximum number of input files which may be overlaid on one graph
+    /// (20 unless specified otherwise)
+    #[structopt(name="max-files", long)]
+    max_files  : Option<usize>,
+    /// If set, the x-axis starts at zero rather than at the first explored count
+    #[structopt(name="x-from-zero", long)]
+    x_from_zero: bool,
+    /// If set, the x-axis starts at the first explored count even though the
+    /// configuration file asks for it to start at zero
+    #[structopt(name="no-x-from-zero", long, conflicts_with="x-from-zero")]
+    no_x_from_zero: bool,
+    /// If set, the format in which the result is printed on the standard
+    /// output: either `json-stats` (the statistics of the traces instead of a
+    /// graph), `yaml` (the same statistics, in yaml) or `png-base64` (the
+    /// graph as a png data uri, e.g. to embed it in a notebook), `csv` (the
+    /// lines of the traces) or `json` (the solutions printed by
+    /// `--print-solution`).
+    #[structopt(name="format", long)]
+    format     : Option<Format>,
+    /// The comma separated columns of the csv output, in order: any of
+    /// `explored`, `lb`, `ub`, `fringe`, `gap` and `time` (the default is
+    /// `explored,lb,ub,fringe`)
+    #[structopt(name="csv-columns", long)]
+    csv_columns: Option<String>,
+    /// If set, the statistics of the traces are also saved in json at the
+    /// specified location (same content as `--format json-stats`).
+    #[structopt(name="stats-out", long)]
+    stats_out  : Option<String>,
+    /// If set, only this bound (either `lb` or `ub`) is plotted
+    #[structopt(name="only", long)]
+    only       : Option<Bound>,
+    /// If set, the lines having explored less nodes than this are not plotted
+    #[structopt(name="from", long)]
+    from       : Option<usize>,
+    /// If set, the lines having explored more nodes than this are not plotted
+    #[structopt(name="to", long)]
+    to         : Option<usize>,
+    /// If set, the lines logged less than this many seconds after the start
+    /// of the search are not plotted (this requires timestamped traces)
+    #[structopt(name="since", long)]
+    since      : Option<f64>,
+    /// If set, the lines logged more than this many seconds after the start
+    /// of the search are not plotted (this requires timestamped traces)
+    #[structopt(name="until", long)]
+    until      : Option<f64>,
+    /// If set, prints the explored count at which each bound value was first
+    /// reached instead of a graph
+    #[structopt(name="milestones", long)]
+    milestones : bool,
+    /// If set, prints a one line sparkline of the upper bound of each trace
+    /// instead of a graph
+    #[structopt(name="sparkline", long)]
+    sparkline  : bool,
+    /// If set, prints the assignment reported in the solution block of each
+    /// trace (space-separated, or as a json array along with `--format json`)
+    /// instead of a graph, e.g. to pipe it into a verifier
+    #[structopt(name="print-solution", long)]
+    print_solution: bool,
+    /// If set, each parsed line of the input is written back in the canonical
+    /// wording of ddo, and the lines which then differ from what was read are
+    /// reported along with their line number (this helps debugging the reading
+    /// of logs in a nonstandard wording, e.g. `Explored 6,700`). The program
+    /// exits with status 1 if there are any.
+    #[structopt(name="round-trip-check", long)]
+    round_trip_check: bool,
+    /// If set, plots the gap between the bounds rather than the bounds
+    /// themselves. The gap is either `absolute`, `relative` (to the upper
+    /// bound) or `both` side by side.
+    #[structopt(name="gap-mode", long)]
+    gap_mode   : Option<GapMode>,
+    /// If set, grid lines are drawn along the given axes of the graph: `none`
+    /// (the default), `x`, `y` or `both`. The grid only shows in the images.
+    #[structopt(name="grid", long)]
+    grid       : Option<GridLines>,
+    /// If set, plots the gap along with the bounds. Since there is no secondary
+    /// axis, the gap is rescaled to the axis of the bounds (the y label tells
+    /// its scale).
+    #[structopt(name="bounds-and-gap", long)]
+    bounds_and_gap: bool,
+    /// If set, the gaps are plotted in log scale using the logarithm in this
+    /// base: either `e`, `2` or `10`. The null gaps are not drawn.
+    #[structopt(name="log-base", long)]
+    log_base   : Option<LogBase>,
+    /// If set, only one line is kept per this many seconds for timestamped
+    /// traces (the last one logged in each time bucket)
+    #[structopt(name="every-seconds", long)]
+    every_seconds: Option<f64>,
+    /// If set, only the lines where the lower or upper bound changed are kept
+    /// (along with the last line). This shrinks the svg outputs of long traces
+    /// without altering the visible curves.
+    #[structopt(name="thin-out", long)]
+    thin_out   : bool,
+    /// Assigns a color to one series of one trace, overriding the palette.
+    /// This is written as `<trace>.<series>=<#RRGGBB>` where the series is
+    /// one of `lb`, `ub`, `fringe`, `gap` or `rgap` (e.g. `a.ub=#FF0000`).
+    /// This option can be repeated.
+    #[structopt(name="color", long, number_of_values=1)]
+    color      : Vec<SeriesColor>,
+    /// If set, the name of the baseline trace. The upper bound of each other
+    /// trace is then plotted as a ratio to the upper bound of the baseline.
+    #[structopt(name="baseline", long)]
+    baseline   : Option<String>,
+    /// If set, the optimum value known from another source (e.g. another
+    /// solver). A horizontal dashed line is drawn at that value on the bounds.
+    #[structopt(name="optimum", long)]
+    optimum    : Option<f64>,
+    /// If set, the final value of each trace is drawn on the bounds plot:
+    /// either as a `line` spanning the whole axis, or as a `point` (or a
+    /// point with a `whisker`) anchored at the explored count of its `Final`
+    /// line, which is less cluttered when many traces are compared.
+    #[structopt(name="final-style", long)]
+    final_style: Option<FinalStyle>,
+    /// If set, the sentinel bounds which solvers log before any actual bound
+    /// is known (e.g. `-inf`-like lower bounds or `+inf`-like upper bounds,
+    /// beyond one billion in magnitude) are left out of the plot so that the
+    /// first lines do not squash the y-axis
+    #[structopt(name="clip", long)]
+    clip       : bool,
+    /// If set, the solve time reported by the `computed in` summary of each
+    /// trace is mentioned in a callout (e.g. `solved in 5.04s`)
+    #[structopt(name="show-time", long)]
+    show_time  : bool,
+    /// If set, the points where the lower bound exceeds the upper bound (which
+    /// reveal a bug of the solver) are emphasized in red and reported
+    #[structopt(name="highlight-crossings", long)]
+    highlight_crossings: bool,
+    /// If set, the upper bounds are capped at this multiple of the final
+    /// optimum (2 unless specified otherwise) so that the convergence region
+    /// is not squashed by the early bounds. The clamped points are drawn at
+    /// the cap in a muted color. This has no effect on traces without a
+    /// `Final` line.
+    #[structopt(name="clamp-ub", long)]
+    clamp_ub   : Option<Option<f64>>,
+    /// If set, the bounds (and gaps) are divided by this factor before they
+    /// are plotted, e.g. 100 to plot costs logged in cents as dollars. This
+    /// must be positive.
+    #[structopt(name="y-scale", long)]
+    y_scale    : Option<f64>,
+    /// If set, the unit of the bounds (once divided by `--y-scale`) which is
+    /// mentioned in the label of the y-axis
+    #[structopt(name="y-unit", long)]
+    y_unit     : Option<String>,
+    /// If set, the path to a csv file whose `explored,value` rows describe a
+    /// reference curve (e.g. the bounds of another solver) to overlay on the
+    /// bounds. The curve is named after the file.
+    #[structopt(name="overlay-csv", long)]
+    overlay_csv: Option<String>,
+    /// If set, a theoretical bound to overlay on the bounds, given as a
+    /// function of the explored count `x` made of numbers, `+ - * /`,
+    /// parentheses, `log` and `sqrt` (e.g. `"100 - 3 * log(x + 1)"`)
+    #[structopt(name="overlay-curve", long)]
+    overlay_curve: Option<Expr>,
+    /// If set, the title of the graph. The `{name}`, `{optimum}`, `{explored}`,
+    /// `{time}` and `{gap}` placeholders are replaced by the values of the
+    /// plotted trace (the first one when several traces are overlaid), e.g.
+    /// `"instance: {name} - opt {optimum} in {time}s"`.
+    #[structopt(name="title-template", long)]
+    title_template: Option<String>,
+    /// If set, the graph is titled after the name of the plotted trace (its
+    /// file stem). When several traces are overlaid, their names are joined.
+    #[structopt(name="title-from-name", long)]
+    title_from_name: bool,
+    /// If set, the graph is also saved at the location obtained by replacing
+    /// the placeholders of this template (the same as in `--title-template`)
+    /// with the values of the plotted trace, e.g. `out/{name}_{optimum}.svg`.
+    /// Path separators are stripped from the substituted values, and a
+    /// numeric suffix is appended to the paths which would clash.
+    #[structopt(name="output-template", long)]
+    output_template: Option<String>,
+    /// If set, the traces (e.g. repeated runs of the same instance with
+    /// different seeds) are aggregated rather than overlaid. With `min-max`,
+    /// the band between the smallest and largest upper bounds is drawn along
+    /// with their median.
+    #[structopt(name="aggregate", long)]
+    aggregate  : Option<Aggregate>,
+    /// If set, draws a bar chart comparing the final number of each trace
+    /// rather than its convergence: either its optimum (`value`, the default)
+    /// or its final gap (`gap`).
+    #[structopt(name="compare-final", long)]
+    compare_final: Option<Option<FinalMetric>>,
+    /// If set, the path to a toml file providing the default options. When
+    /// this is not set, the defaults are read from `.ddo-plotter.toml` if
+    /// that file exists in the current directory.
+    #[structopt(name="config", long)]
+    config     : Option<String>,
+    /// If set, compares two runs for regression testing (`--bench old new`):
+    /// prints how many fewer nodes the new run needed to close its gap and
+    /// whether both runs reached the same optimum. The program then exits with
+    /// a non zero status when the new run regressed.
+    #[structopt(name="bench", long, number_of_values=2)]
+    bench      : Option<Vec<String>>,
+    /// The gap within which a run is deemed to have closed by `--bench`
+    /// (0 unless specified otherwise, that is until the optimum is proven)
+    #[structopt(name="bench-tolerance", long)]
+    bench_tolerance: Option<f64>,
+    /// The verbosity of the diagnostics printed on the standard error: either
+    /// `off`, `error`, `warn` (the default), `info`, `debug` or `trace`. When
+    /// this is not set, the `RUST_LOG` environment variable is honored.
+    #[structopt(name="log-level", long)]
+    log_level  : Option<LevelFilter>,
+    /// The defaults loaded from the configuration file
+    #[structopt(skip)]
+    defaults   : Defaults,
+    /// The reference curve loaded from the overlay csv file
+    #[structopt(skip)]
+    overlay    : Option<Overlay>,
+}
+
+/// The maximum number of files which may be overlaid unless specified otherwise
+const MAX_FILES: usize = 20;
+/// The ratio between the largest and smallest explored counts of overlaid
+/// traces above which the smallest traces become unreadable.
+const EXPLORED_RATIO_WARNING: f64 = 50.0;
+/// The maximum number of characters of a sparkline
+const SPARKLINE_WIDTH: usize = 60;
+/// The environment variable giving the dimension of the text output when it
+/// is not printed to a terminal
+const DIM_ENV: &str = "DDO_PLOTTER_DIM";
+/// The pseudo file name which stands for the standard input among the inputs
+const STDIN_PATH: &str = "-";
+/// The name of the trace read from the standard input along with files
+const STDIN_NAME: &str = "stdin";
+/// The multiple of the final optimum at which the upper bounds are clamped
+/// unless specified otherwise
+const CLAMP_UB_FACTOR: f64 = 2.0;
+/// The exit status when the graph could not be saved to one of the output
+/// files (set apart from the other errors, which exit with status 1)
+const SAVE_FAILURE_STATUS: i32 = 2;
+
+impl Args {
+    /// Loads the default options from the configuration file (if any)
+    fn load_defaults(mut self) -> Self {
+        let path = match &self.config {
+            Some(path) => Some(PathBuf::from(path)),
+            None       => Some(PathBuf::from(DEFAULT_CONFIG_FILE)).filter(|p| p.exists())
+        };
+        if let Some(path) = path {
+            self.defaults = Defaults::load(&path).unwrap_or_else(|e| {
+                eprintln!("Cannot load the configuration from '{}': {}", path.display(), e);
+                exit(1)
+            });
+        }
+        self
+    }
+    /// Loads the reference curve from the overlay csv file (if any)
+    fn load_overlay(mut self) -> Self {
+        if let Some(path) = &self.overlay_csv {
+            let (overlay, skipped) = Overlay::load(Path::new(path)).unwrap_or_else(|e| {
+                eprintln!("Cannot open file '{}': {}", path, e);
+                exit(1)
+            });
+            if skipped > 0 {
+                warn!("{} malformed rows of '{}' were skipped", skipped, path);
+            }
+            self.overlay = Some(overlay);
+        }
+        self
+    }
+    /// The paths of the input files: those given with `--input` followed by
+    /// those listed in the `--input-list` file. Returns None when the trace
+    /// is to be read from the standard input.
+    fn input_files(&self) -> Option<Vec<String>> {
+        let mut fnames = self.input.clone().unwrap_or_default();
+        if let Some(list) = &self.input_list {
+            let text = read_to_string(list).unwrap_or_else(|e| {
+                eprintln!("Cannot open file '{}': {}", list, e);
+                exit(1)
+            });
+            fnames.extend(text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string));
+        }
+        if self.input.is_none() && self.input_list.is_none() {
+            None
+        } else {
+            Some(fnames)
+        }
+    }
+    /// The maximum number of files which may be overlaid on one graph
+    fn max_files(&self) -> usize {
+        self.max_files.or(self.defaults.max_files).unwrap_or(MAX_FILES)
+    }
+    /// The final number compared by the bar chart (if one is to be drawn)
+    fn final_metric(&self) -> Option<FinalMetric> {
+        self.compare_final.map(|metric| metric.unwrap_or(FinalMetric::Value))
+    }
+    /// The wording of the log lines of the version of ddo which wrote the traces
+    fn log_format(&self) -> &'static LogFormat {
+        match &self.ddo_version {
+            None          => LogFormat::latest(),
+            Some(version) => LogFormat::of(version).unwrap_or_else(|| {
+                eprintln!("Unknown ddo version '{}' (expected one of: {})",
+                          version, LogFormat::versions().join(", "));
+                exit(1)
+            })
+        }
+    }
+    /// The length above which a line of the input is skipped
+    fn max_line_length(&self) -> usize {
+        self.max_line_length.unwrap_or(MAX_LINE_LENGTH)
+    }
+    /// The options to use when reading a trace
+    fn read_options(&self) -> ReadOptions {
+        ReadOptions {
+            max_line_length: self.max_line_length(),
+            format         : self.log_format(),
+            select         : self.select_lines.clone(),
+            ndjson         : self.ndjson
+        }
+    }
+    /// The dimension of the graph (if specified)
+    fn dimension(&self) -> Option<Dimension> {
+        self.dimension.or_else(|| self.defaults.dimension())
+    }
+    /// The dimension of the pages of the graph. With `--square`, this is the
+    /// largest square fitting in the dimension of the graph (or in plotlib's
+    /// default page when it is not specified).
+    fn page_dimension(&self) -> Option<Dimension> {
+        if self.square {
+            let page = Dimension::new(PAGE_WIDTH, PAGE_HEIGHT).expect("Invalid default page size");
+            Some(self.dimension().unwrap_or(page).square())
+        } else {
+            self.dimension()
+        }
+    }
+    /// The dimension of the text output. Unless it is specified, this is the
+    /// size of the terminal or, when there is none (e.g. in a headless CI),
+    /// the dimension given by the `DDO_PLOTTER_DIM` environment variable.
+    /// With `--square`, this is the largest square fitting in that dimension.
+    fn text_dimension(&self) -> Dimension {
+        let dimension = self.dimension()
+            .or_else(terminal_dimension)
+            .or_else(env_dimension)
+            .unwrap_or_default();
+        if self.square { dimension.square() } else { dimension }
+    }
+    /// The quantity on the x-axis of the bounds plot
+    fn x_field(&self) -> XField {
+        match self.x_field {
+            Some(field)                => field,
+            None if self.relative_time => XField::Time,
+            None                       => XField::Explored
+        }
+    }
+    /// The options to use when rendering a view
+    fn view_options(&self) -> ViewOptions {
+        let defaults = &self.defaults;
+        let mut options = ViewOptions {
+            rich_legend: Defaults::switch(cli_switch(self.rich_legend, self.no_rich_legend), defaults.rich_legend),
+            segments   : Defaults::switch(cli_switch(self.segments,    self.no_segments),    defaults.segments),
+            x_from_zero: Defaults::switch(cli_switch(self.x_from_zero, self.no_x_from_zero), defaults.x_from_zero),
+            only       : self.only,
+            colors     : self.color.clone(),
+            optimum    : self.optimum,
+            overlay    : self.overlay.clone(),
+            overlay_curve: self.overlay_curve.clone(),
+            clamp_ub   : self.clamp_ub.map(|factor| factor.unwrap_or(CLAMP_UB_FACTOR)),
+            log_base   : self.log_base,
+            legend_counts: self.legend_counts,
+            step       : self.step,
+            fringe_highwater: self.fringe_highwater,
+            no_final_in_series: self.no_final_in_series,
+            two_phase  : self.two_phase,
+            x_ticks    : self.x_ticks,
+            x_field    : self.x_field(),
+            relative_time: self.relative_time,
+            bin_x      : self.bin_x,
+            velocity_window: self.velocity.flatten(),
+            final_style: self.final_style,
+            clip       : self.clip,
+            y_scale    : self.y_scale.unwrap_or(1.0),
+            y_unit     : self.y_unit.clone(),
+            show_time  : self.show_time,
+            grid       : self.grid.unwrap_or(GridLines::Off),
+            highlight_crossings: self.highlight_crossings,
+            ..ViewOptions::default()
+        };
+        if let Some(palette) = &defaults.palette {
+            options.palette = palette.clone();
+        }
+        if let Some(size) = self.marker_size.or(defaults.marker_size) {
+            options.marker_size = size;
+        }
+        if let Some(label) = &defaults.x_label {
+            options.x_label = label.clone();
+        }
+        options.y_label = defaults.y_label.clone();
+        options
+    }
+}
+
+/// The state of a switch given on the command line by a flag and its negation
+/// (None when neither is given)
+fn cli_switch(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _)      => Some(true),
+        (_, true)      => Some(false),
+        (false, false) => None
+    }
+}
+
+/// Sets up the logger which reports the diagnostics (warnings about skipped
+/// lines, missing values, ...) on the standard error, so that they never end
+/// up mixed with the plot (or report) printed on the standard output.
+fn init_logger(level: Option<LevelFilter>) {
+    let mut logger = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn"));
+    if let Some(level) = level {
+        logger.filter_level(level);
+    }
+    logger.format_timestamp(None).format_target(false).init();
 }
 
 fn main() {
     let args = Args::from_args();
+    init_logger(args.log_level);
+    let args = args.load_defaults().load_overlay();
+
+    if args.marker_size.is_some_and(|size| size <= 0.0) {
+        eprintln!("The size given to --marker-size must be positive");
+        exit(1);
+    }
+    if args.y_scale.is_some_and(|scale| !(scale > 0.0 && scale.is_finite())) {
+        eprintln!("The factor given to --y-scale must be positive");
+        exit(1);
+    }
+    if args.bin_x == Some(0) {
+        eprintln!("The number of bins given to --bin-x must be positive");
+        exit(1);
+    }
+    if args.velocity == Some(Some(0)) {
+        eprintln!("The window given to --velocity must be positive");
+        exit(1);
+    }
+    if args.x_ticks == Some(0) {
+        eprintln!("The number of ticks given to --x-ticks must be positive");
+        exit(1);
+    }
+    if args.svg_stdout {
+        let conflicts = [("--output", !args.output.is_empty()), ("--output-template", args.output_template.is_some()),
+                         ("--separate", args.separate), ("--format", args.format.is_some()), ("--bench", args.bench.is_some()),
+                         ("--stats", args.stats), ("--summary-only", args.summary_only), ("--milestones", args.milestones),
+                         ("--sparkline", args.sparkline), ("--print-solution", args.print_solution),
+                         ("--round-trip-check", args.round_trip_check)];
+        if let Some((name, _)) = conflicts.iter().find(|(_, set)| *set) {
+            eprintln!("--svg-stdout cannot be used along with {}", name);
+            exit(1);
+        }
+    }
+
+    let reading = args.read_options();
+    if let Some(runs) = &args.bench {
+        let runs = runs.iter()
+            .map(|fname| load_trace(fname, &reading).map(|(trace, _)| trace).unwrap_or_else(|e| {
+                eprintln!("Cannot open file '{}': {}", fname, e);
+                exit(1)
+            }))
+            .collect::<Vec<Trace>>();
+        let passed = bench(&runs[0], &runs[1], args.bench_tolerance.unwrap_or(0.0));
+        exit(if passed { 0 } else { 1 });
+    }
+
+    let fnames = args.input_files();
+    if args.round_trip_check {
+        if args.ndjson {
+            eprintln!("--round-trip-check cannot be used along with --ndjson");
+            exit(1);
+        }
+        let passed = print_round_trip_check(fnames.as_deref(), &reading);
+        exit(if passed { 0 } else { 1 });
+    }
+    let traces =
+        if let Some(fnames) = &fnames {
+            if !args.separate && fnames.len() > args.max_files() {
+                eprintln!("Refusing to overlay {} traces on one graph (the limit is {}). \
+                           Either narrow down the list of inputs, plot them with \
+                           --separate or raise the limit with --max-files.",
+                          fnames.len(), args.max_files());
+                exit(1);
+            }
+            if fnames.iter().filter(|fname| *fname == STDIN_PATH).count() > 1 {
+                eprintln!("The standard input ('{}') can only be read once", STDIN_PATH);
+                exit(1);
+            }
+            let mut failed = false;
+            let traces  = fnames.iter().zip(load_traces(fnames, &reading)).filter_map(|(fname, trace)|
+                trace.map(|(trace, skipped)| {
+                    if skipped > 0 {
+                        warn!("skipped {} line(s) longer than {} bytes in '{}'", skipped, reading.max_line_length, fname);
+                    }
+                    trace
+                }).map_err(|e| {
+                    eprintln!("Cannot open file '{}': {}", fname, e);
+                    failed = true;
+                }).ok()
+            ).collect::<Vec<Trace>>();
+            if failed {
+                exit(1);
+            }
+            traces
+        } else {
+            let (trace, skipped) = read_stdin(&reading);
+            if skipped > 0 {
+                warn!("skipped {} line(s) longer than {} bytes in the standard input",
+                         skipped, reading.max_line_length);
+            }
+            vec![trace]
+        };
+
+    let traces =
+        if let Some(name) = &args.name {
+            if traces.len() != 1 {
+                eprintln!("--name requires a single input trace (got {})", traces.len());
+                exit(1);
+            }
+            traces.into_iter().map(|trace| trace.with_name(name.as_str())).collect()
+        } else {
+            traces
+        };
+
+    let traces =
+        traces.iter().enumerate().map(|(i, trace)| {
+            if !trace.has_out_of_order_final() {
+                trace.clone()
+            } else if args.sort {
+                trace.with_final_last()
+            } else {
+                warn!("the Final line of the trace '{}' precedes some ongoing lines \
+                          (use --sort to move it after them)", trace_name(trace, i));
+                trace.clone()
+            }
+        }).collect::<Vec<Trace>>();
 
     let traces =
-        if let Some(fnames) = &args.input {
-            fnames.iter().map(|fname|
-                Trace::try_from(Path::new(fname)).expect("Cannot open file")
-            ).collect::<Vec<Trace>>()
+        match args.only_lines {
+            Some(LineKind::Ongoing) => traces.iter().map(|trace| trace.filter(LogLine::is_ongoing)).collect(),
+            Some(LineKind::Final)   => traces.iter().map(|trace| trace.filter(LogLine::is_final)).collect(),
+            None                    => traces
+        };
+
+    let traces =
+        if args.untangle {
+            traces.iter().map(Trace::monotone_envelope).collect()
+        } else {
+            traces
+        };
+
+    let traces =
+        if let Some(n) = args.skip {
+            traces.iter().map(|trace| trace.skip(n)).collect()
+        } else {
+            traces
+        };
+
+    let traces =
+        if args.from.is_some() || args.to.is_some() {
+            let windows = traces.iter().map(|trace| trace.window(args.from, args.to)).collect::<Vec<Trace>>();
+            let mut all_empty = true;
+            for (i, (trace, window)) in traces.iter().zip(windows.iter()).enumerate() {
+                match trace.explored_range() {
+                    Some((min, max)) if window.lines.is_empty() =>
+                        warn!("the requested range {} is outside the range {}–{} of the trace '{}'",
+                              requested_range(args.from, args.to), min, max, trace_name(trace, i)),
+                    _ => all_empty &= window.lines.is_empty()
+                }
+            }
+            if all_empty {
+                eprintln!("No line of the traces lies in the requested range {}", requested_range(args.from, args.to));
+                exit(1);
+            }
+            windows
+        } else {
+            traces
+        };
+
+    if args.relative_time && args.x_field() != XField::Time {
+        eprintln!("--relative-time requires the time x-axis (--x-field time)");
+        exit(1);
+    }
+    if args.x_field() != XField::Explored {
+        let views = [("--gap-mode", args.gap_mode.is_some()), ("--fringe", args.fringe), ("--fringe-highwater", args.fringe_highwater),
+                     ("--fringe-ratio", args.fringe_ratio), ("--velocity", args.velocity.is_some()), ("--lb-gain", args.lb_gain),
+                     ("--ub-progress", args.ub_progress), ("--aggregate", args.aggregate.is_some()), ("--baseline", args.baseline.is_some())];
+        if let Some((name, _)) = views.iter().find(|(_, set)| *set) {
+            eprintln!("The {} view is plotted against the explored count: it cannot \
+                       be used along with another x-axis (--x-field)", name);
+            exit(1);
+        }
+    }
+    if args.x_field() == XField::Time {
+        for (i, trace) in traces.iter().enumerate() {
+            if !trace.has_timestamps() {
+                eprintln!("The trace '{}' is not timestamped: the time x-axis \
+                           requires each line to start with a [<seconds>s] prefix",
+                          trace_name(trace, i));
+                exit(1);
+            }
+        }
+    }
+
+    let traces =
+        if args.since.is_some() || args.until.is_some() {
+            traces.iter().enumerate().map(|(i, trace)| {
+                if !trace.has_timestamps() {
+                    eprintln!("The trace '{}' is not timestamped: --since and --until \
+                               require each line to start with a [<seconds>s] prefix",
+                              trace_name(trace, i));
+                    exit(1);
+                }
+                trace.window_time(args.since, args.until)
+            }).collect()
+        } else {
+            traces
+        };
+
+    let traces =
+        if let Some(secs) = args.every_seconds {
+            if secs <= 0.0 {
+                eprintln!("The duration given to --every-seconds must be positive");
+                exit(1);
+            }
+            traces.iter().enumerate().map(|(i, trace)| {
+                if !trace.has_timestamps() {
+                    eprintln!("The trace '{}' is not timestamped: --every-seconds \
+                               requires each line to start with a [<seconds>s] prefix",
+                              trace_name(trace, i));
+                    exit(1);
+                }
+                trace.resample_time(secs)
+            }).collect()
+        } else {
+            traces
+        };
+
+    let traces =
+        if args.thin_out {
+            traces.iter().map(Trace::changes_only).collect()
+        } else {
+            traces
+        };
+
+    if let Some(out) = &args.stats_out {
+        write(out, stats_json(&traces)).unwrap_or_else(|e| {
+            eprintln!("Cannot save the statistics to '{}': {}", out, e);
+            exit(1)
+        });
+    }
+
+    if args.stats {
+        print_stats(&traces);
+        return;
+    }
+    if args.summary_only {
+        print_summary(&traces);
+        return;
+    }
+    if args.milestones {
+        print_milestones(&traces);
+        return;
+    }
+    if args.sparkline {
+        print_sparklines(&traces);
+        return;
+    }
+    if args.print_solution {
+        print_solutions(&traces, args.format == Some(Format::Json));
+        return;
+    }
+    if args.format == Some(Format::Json) {
+        eprintln!("--format json only applies to --print-solution");
+        exit(1);
+    }
+    if args.format == Some(Format::JsonStats) {
+        println!("{}", stats_json(&traces));
+        return;
+    }
+    if args.format == Some(Format::Yaml) {
+        print!("{}", stats_yaml(&traces));
+        return;
+    }
+    if args.format == Some(Format::Csv) {
+        let columns = match &args.csv_columns {
+            Some(txt) => CsvColumn::parse_list(txt).unwrap_or_else(|e| {
+                eprintln!("Invalid --csv-columns '{}': {}", txt, e);
+                exit(1)
+            }),
+            None      => DEFAULT_CSV_COLUMNS.to_vec()
+        };
+        print!("{}", lines_csv(&traces, &columns));
+        return;
+    }
+
+    if args.aggregate.is_some() && args.separate {
+        eprintln!("--aggregate cannot be used along with --separate");
+        exit(1);
+    }
+    if args.title_from_name && args.title_template.is_some() {
+        eprintln!("--title-from-name cannot be used along with --title-template");
+        exit(1);
+    }
+    if args.small_multiples {
+        let conflicts = [("--separate", args.separate), ("--aggregate", args.aggregate.is_some()),
+                         ("--baseline", args.baseline.is_some()), ("--compare-final", args.compare_final.is_some())];
+        if let Some((name, _)) = conflicts.iter().find(|(_, set)| *set) {
+            eprintln!("--small-multiples cannot be used along with {}", name);
+            exit(1);
+        }
+    }
+    if let Some(metric) = args.final_metric() {
+        if args.separate {
+            eprintln!("--compare-final cannot be used along with --separate");
+            exit(1);
+        }
+        for (i, trace) in traces.iter().enumerate() {
+            if final_metric(trace, metric).is_none() {
+                warn!("the trace '{}' has no final value, it is left out of \
+                          the comparison", trace_name(trace, i));
+            }
+        }
+    }
+
+    let baseline = args.baseline.as_ref().map(|name| {
+        if args.separate {
+            eprintln!("--baseline cannot be used along with --separate");
+            exit(1);
+        }
+        traces.iter().enumerate()
+            .position(|(i, trace)| trace_name(trace, i) == *name)
+            .unwrap_or_else(|| {
+                eprintln!("There is no trace named '{}' to use as baseline", name);
+                exit(1)
+            })
+    });
+
+    if args.clamp_ub.is_some() {
+        for (i, trace) in traces.iter().enumerate() {
+            if trace.final_value().is_none() {
+                warn!("the trace '{}' has no Final line, its upper bound \
+                          is not clamped", trace_name(trace, i));
+            }
+        }
+    }
+    if args.highlight_crossings {
+        for (i, trace) in traces.iter().enumerate() {
+            let crossings = trace.crossings();
+            if let Some(first) = crossings.first() {
+                warn!("the lower bound of the trace '{}' exceeds its upper bound on {} line(s), \
+                          first after {} explored nodes", trace_name(trace, i), crossings.len(), first.explored());
+            }
+        }
+    }
+
+    let saved =
+        if args.separate {
+            check_output_paths(&args.output, traces.len(), true);
+            let mut saved = true;
+            let mut used  = HashSet::new();
+            for (i, trace) in traces.iter().enumerate() {
+                let mut outputs = args.output.iter().map(|out| output_path(out, trace, i)).collect::<Vec<_>>();
+                outputs.extend(args.output_template.as_ref().map(|t| templated_output(t, trace, i, &mut used)));
+                let title       = args.title_template.as_ref().map(|t| title(t, trace, i))
+                    .or_else(|| if args.title_from_name { Some(trace_name(trace, i)) } else { None });
+                saved &= render(&args, std::slice::from_ref(trace), None, &outputs, title.as_deref());
+            }
+            saved
         } else {
-            vec![Trace::from(BufReader::new(stdin()).lines())]
+            warn_if_explored_ranges_differ(&traces);
+            let title = match (&args.title_template, traces.first()) {
+                (Some(t), Some(trace))    => Some(title(t, trace, 0)),
+                _ if args.title_from_name => Some(joined_names(&traces)),
+                _                         => None
+            };
+            check_output_paths(&args.output, traces.len(), false);
+            let mut outputs = match traces.first() {
+                Some(trace) => args.output.iter().map(|out| output_path(out, trace, 0)).collect(),
+                None        => args.output.clone()
+            };
+            if let (Some(t), Some(trace)) = (&args.output_template, traces.first()) {
+                outputs.push(templated_output(t, trace, 0, &mut HashSet::new()));
+            }
+            render(&args, &traces, baseline, &outputs, title.as_deref())
         };
 
-    let view =
-        if args.fringe {
-            fringe_view(&traces)
+    if !saved {
+        exit(SAVE_FAILURE_STATUS);
+    }
+}
+
+/// Checks the `{name}` placeholders of the output paths given the number of
+/// plotted traces: each of several traces plotted separately needs a path of
+/// its own, whereas the graph overlaying several traces cannot be named after
+/// one of them.
+fn check_output_paths(outputs: &[String], traces: usize, separate: bool) {
+    if traces <= 1 {
+        return;
+    }
+    for out in outputs {
+        if separate && !out.contains("{name}") {
+            eprintln!("The output path '{}' must contain a {{name}} placeholder \
+                       when several traces are plotted separately", out);
+            exit(1);
+        }
+        if !separate && out.contains("{name}") {
+            eprintln!("The output path '{}' cannot contain a {{name}} placeholder \
+                       when several traces are overlaid (use --separate)", out);
+            exit(1);
+        }
+    }
+}
+
+/// The explored range given by `--from` and `--to` (either may be missing)
+fn requested_range(from: Option<usize>, to: Option<usize>) -> String {
+    match (from, to) {
+        (Some(from), Some(to)) => format!("{}–{}", from, to),
+        (Some(from), None)     => format!("{}–", from),
+        (None, Some(to))       => format!("0–{}", to),
+        (None, None)           => "0–".to_string()
+    }
+}
+
+/// Warns the user when the traces to overlay have explored so different
+/// numbers of nodes that the smallest ones will be squashed near the origin.
+fn warn_if_explored_ranges_differ(traces: &[Trace]) {
+    let max_explored = traces.iter()
+        .filter_map(|trace| trace.lines.iter().map(|ll| ll.explored()).max())
+        .filter(|explored| *explored > 0)
+        .collect::<Vec<usize>>();
+
+    let smallest = max_explored.iter().min();
+    let largest  = max_explored.iter().max();
+    if let (Some(&smallest), Some(&largest)) = (smallest, largest) {
+        let ratio = largest as f64 / smallest as f64;
+        if ratio > EXPLORED_RATIO_WARNING {
+            warn!("the overlaid traces explored between {} and {} nodes ({:.0}x). \
+                      The smallest traces will hardly be visible; consider plotting them \
+                      with --separate or zooming in with --from/--to.",
+                     smallest, largest, ratio);
+        }
+    }
+}
+
+/// The size of the terminal the graph is printed to (if it is printed to one)
+fn terminal_dimension() -> Option<Dimension> {
+    if !stdout().is_terminal() {
+        return None;
+    }
+    let (width, height) = term_size::dimensions()?;
+    Dimension::new(width as u32, height as u32).ok()
+}
+
+/// The dimension given by the `DDO_PLOTTER_DIM` environment variable (if it
+/// is set). An invalid value is ignored with a warning.
+fn env_dimension() -> Option<Dimension> {
+    let value = std::env::var(DIM_ENV).ok()?;
+    value.parse::<Dimension>()
+        .map_err(|e| warn!("ignored the value '{}' of {}: {}", value, DIM_ENV, e))
+        .ok()
+}
+
+/// Parses the trace piped on the standard input. Returns it along with the
+/// number of over-long lines which were skipped.
+fn read_stdin(options: &ReadOptions) -> (Trace, usize) {
+    let mut lines = BoundedLines::new(BufReader::new(stdin()), options.max_line_length);
+    let trace     = Trace::try_from_lines_with(&mut lines, options)
+        .unwrap_or_else(|truncated| {
+            warn!("the standard input could not be read to its end: {}", truncated);
+            truncated.trace
+        });
+    (trace, lines.skipped)
+}
+
+/// Parses the trace from the given file, or from the standard input when the
+/// file name is `-` (that trace is then named after `STDIN_NAME`).
+fn load_trace(fname: &str, options: &ReadOptions) -> Result<(Trace, usize), std::io::Error> {
+    if fname == STDIN_PATH {
+        let (trace, skipped) = read_stdin(options);
+        Ok((trace.with_name(STDIN_NAME), skipped))
+    } else {
+        Trace::load(Path::new(fname), options)
+    }
+}
+
+/// Parses the traces from the given files. The results are in the same order
+/// as the file names.
+#[cfg(not(feature = "parallel"))]
+fn load_traces(fnames: &[String], options: &ReadOptions) -> Vec<Result<(Trace, usize), std::io::Error>> {
+    fnames.iter()
+        .map(|fname| load_trace(fname, options))
+        .collect()
+}
+/// Parses the traces from the given files in parallel. The results are in the
+/// same order as the file names.
+#[cfg(feature = "parallel")]
+fn load_traces(fnames: &[String], options: &ReadOptions) -> Vec<Result<(Trace, usize), std::io::Error>> {
+    use rayon::prelude::*;
+    fnames.par_iter()
+        .map(|fname| load_trace(fname, options))
+        .collect()
+}
+
+/// Prints the summary statistics of each trace (one line per trace)
+fn print_stats(traces: &[Trace]) {
+    for (i, trace) in traces.iter().enumerate() {
+        let name = trace_name(trace, i);
+        if let Some(stats) = trace.stats() {
+            let rate   = stats.decay_rate.map_or("n/a".to_string(), |k| format!("{:e}", k));
+            let proven = if stats.proven { "proven" } else { "NOT PROVEN" };
+            let memory = if stats.fringe_growing > FRINGE_GROWING_THRESHOLD { " (FRINGE KEEPS GROWING)" } else { "" };
+            println!("{}: explored {}, gap {}, {}, gap decay rate {}, area under gap {}, fringe growing {:.0}%{}",
+                     name, stats.explored, stats.gap, proven, rate, stats.gap_area, 100.0 * stats.fringe_growing, memory);
+        } else {
+            println!("{}: empty trace", name);
+        }
+    }
+}
+
+/// Serializes the lines of all traces to csv, in the given columns. When there
+/// are several traces, a leading `trace` column tells them apart.
+fn lines_csv(traces: &[Trace], columns: &[CsvColumn]) -> String {
+    let named  = traces.len() > 1;
+    let header = columns.iter().map(|c| c.name()).collect::<Vec<_>>().join(",");
+    let mut csv = if named { format!("trace,{}\n", header) } else { format!("{}\n", header) };
+    for (i, trace) in traces.iter().enumerate() {
+        for ll in trace.lines.iter() {
+            if named {
+                csv += &format!("{},", trace_name(trace, i));
+            }
+            csv += &columns.iter().map(|c| c.value(ll)).collect::<Vec<_>>().join(",");
+            csv += "\n";
+        }
+    }
+    csv
+}
+
+/// Prints the headline numbers of each trace (one line per trace)
+fn print_summary(traces: &[Trace]) {
+    for (i, trace) in traces.iter().enumerate() {
+        let name = trace_name(trace, i);
+        if let Some(stats) = trace.stats() {
+            let converged = if stats.proven { "yes" } else { "no" };
+            let time      = stats.time.map_or("n/a".to_string(), |t| format!("{}s", t));
+            println!("{}: explored {}, lb {}, ub {}, gap {}, converged {}, time {}",
+                     name, stats.explored, stats.lb, stats.ub, stats.gap, converged, time);
         } else {
-            bounds_view(&traces)
+            println!("{}: empty trace", name);
+        }
+    }
+}
+
+/// Prints the table of the bound improvement milestones of each trace
+fn print_milestones(traces: &[Trace]) {
+    for (i, trace) in traces.iter().enumerate() {
+        println!("{}", trace_name(trace, i));
+        println!("  {:<5} {:>12} {:>12}", "bound", "value", "explored");
+        for (value, explored) in trace.ub_milestones() {
+            println!("  {:<5} {:>12} {:>12}", "UB", value, explored);
+        }
+        for (value, explored) in trace.lb_milestones() {
+            println!("  {:<5} {:>12} {:>12}", "LB", value, explored);
+        }
+    }
+}
+
+/// Prints the lines of each input (or of the standard input when no file is
+/// given) which do not read the same once parsed and written back in the
+/// canonical wording of ddo, each one as a diff between the line read and the
+/// line written. Returns true iff all lines round-trip.
+fn print_round_trip_check(fnames: Option<&[String]>, options: &ReadOptions) -> bool {
+    let stdin_only = [STDIN_PATH.to_string()];
+    let mut passed = true;
+    for fname in fnames.unwrap_or(&stdin_only) {
+        let result = if fname == STDIN_PATH {
+            round_trip_check(stdin().lock().lines(), options)
+        } else {
+            File::open(fname).and_then(|file| round_trip_check(BufReader::new(file).lines(), options))
         };
+        let (parsed, mismatches) = result.unwrap_or_else(|e| {
+            eprintln!("Cannot read file '{}': {}", fname, e);
+            exit(1)
+        });
+        for mismatch in mismatches.iter() {
+            println!("{}:{}: the line does not round-trip", fname, mismatch.line_no);
+            println!("  - {}", mismatch.read);
+            println!("  + {}", mismatch.written);
+        }
+        println!("{}: {} of {} line(s) round-trip", fname, parsed - mismatches.len(), parsed);
+        passed &= mismatches.is_empty();
+    }
+    passed
+}
+
+/// Prints the sparkline of the upper bound of each trace (one line per trace)
+fn print_sparklines(traces: &[Trace]) {
+    for (i, trace) in traces.iter().enumerate() {
+        let ub = trace.ub_explored().into_iter().map(|(_, ub)| ub).collect::<Vec<f64>>();
+        println!("{}: {}", trace_name(trace, i), sparkline(&ub, SPARKLINE_WIDTH));
+    }
+}
 
-    if let Some(out) = &args.output {
-        Page::single(&view).save(out).expect("Cannot save output");
+/// Prints the assignment of the solution block of each trace (one line per
+/// trace), either space-separated or as a json array. The traces which have
+/// no solution block are reported with a warning.
+fn print_solutions(traces: &[Trace], json: bool) {
+    for (i, trace) in traces.iter().enumerate() {
+        match trace.solution() {
+            Some(solution) if json =>
+                println!("{}", serde_json::to_string(solution).expect("Cannot serialize solution")),
+            Some(solution) =>
+                println!("{}", solution.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" ")),
+            None =>
+                warn!("the trace '{}' has no solution block", trace_name(trace, i))
+        }
+    }
+}
+
+/// Compares the new run to the old one and prints the verdict: the speedup is
+/// the fraction of the nodes the old run explored to close its gap (to within
+/// the tolerance) which the new run spared. Returns false when the new run
+/// regressed, that is when it needed more nodes or reached another optimum.
+fn bench(old: &Trace, new: &Trace, tolerance: f64) -> bool {
+    let describe = |explored: Option<f64>| explored.map_or("never".to_string(), |x| format!("{:.0} nodes", x));
+    let old_x    = old.explored_to_close(tolerance);
+    let new_x    = new.explored_to_close(tolerance);
+    println!("old: {} to close the gap to {}", describe(old_x), tolerance);
+    println!("new: {} to close the gap to {}", describe(new_x), tolerance);
+
+    let faster = match (old_x, new_x) {
+        (Some(old_x), Some(new_x)) => {
+            println!("speedup: {:.1}%", 100.0 * (old_x - new_x) / old_x.max(1.0));
+            new_x <= old_x
+        },
+        (None, _) => true,
+        (Some(_), None) => false
+    };
+    let same_optimum = match (old.final_value(), new.final_value()) {
+        (Some(old_v), Some(new_v)) if old_v == new_v => {
+            println!("optimum: PASS ({})", new_v);
+            true
+        },
+        (old_v, new_v) => {
+            let describe = |v: Option<i32>| v.map_or("none".to_string(), |v| v.to_string());
+            println!("optimum: FAIL ({} vs {})", describe(old_v), describe(new_v));
+            false
+        }
+    };
+    faster && same_optimum
+}
+
+/// Prints the final number of each trace (one line per trace). This stands
+/// for the bar chart in the terminal, where plotlib cannot draw it.
+fn print_finals(traces: &[Trace], metric: FinalMetric) {
+    for (i, trace) in traces.iter().enumerate() {
+        if let Some(value) = final_metric(trace, metric) {
+            println!("{}: {}", trace_name(trace, i), value);
+        }
+    }
+}
+
+/// Serializes the summary statistics of all (non empty) traces to json
+fn stats_json(traces: &[Trace]) -> String {
+    let stats = traces.iter().filter_map(Trace::stats).collect::<Vec<_>>();
+    serde_json::to_string_pretty(&stats).expect("Cannot serialize statistics")
+}
+
+/// Serializes the summary statistics of all (non empty) traces to yaml
+fn stats_yaml(traces: &[Trace]) -> String {
+    let stats = traces.iter().filter_map(Trace::stats).collect::<Vec<_>>();
+    serde_yaml::to_string(&stats).expect("Cannot serialize statistics")
+}
+
+/// The names of the given traces, joined to title the graph overlaying them.
+fn joined_names(traces: &[Trace]) -> String {
+    traces.iter().enumerate()
+        .map(|(i, trace)| trace_name(trace, i))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Substitutes the `{name}` placeholder of the output template with the name
+/// of the given trace (or its position when the trace has no name).
+fn output_path(template: &str, trace: &Trace, index: usize) -> String {
+    template.replace("{name}", &trace_name(trace, index))
+}
+
+/// Substitutes the placeholders of the title template with the values of the
+/// given trace.
+fn title(template: &str, trace: &Trace, index: usize) -> String {
+    fill_template(template, &placeholders(trace, index))
+}
+
+/// Substitutes the placeholders of the output template with the values of
+/// the given trace, stripped of the path separators so that they cannot
+/// escape the directory of the template. When the resulting path was already
+/// used, a numeric suffix is appended to its stem (e.g. `a_2.svg`).
+fn templated_output(template: &str, trace: &Trace, index: usize, used: &mut HashSet<String>) -> String {
+    let values = placeholders(trace, index).into_iter()
+        .map(|(key, value)| (key, value.replace(['/', '\\'], "")))
+        .collect::<Vec<_>>();
+    let path   = fill_template(template, &values);
+
+    let mut unique = path.clone();
+    let mut suffix = 1;
+    while !used.insert(unique.clone()) {
+        suffix += 1;
+        let p    = Path::new(&path);
+        let stem = p.file_stem().map_or(String::new(), |s| s.to_string_lossy().to_string());
+        let file = match p.extension() {
+            Some(ext) => format!("{}_{}.{}", stem, suffix, ext.to_string_lossy()),
+            None      => format!("{}_{}", stem, suffix)
+        };
+        unique = p.with_file_name(file).to_string_lossy().to_string();
+    }
+    unique
+}
+
+/// The values of the placeholders of the templates for the given trace. The
+/// values which are unknown (e.g. the optimum of a trace which did not run to
+/// completion) are written `n/a`.
+fn placeholders(trace: &Trace, index: usize) -> Vec<(&'static str, String)> {
+    let na    = || "n/a".to_string();
+    let stats = trace.stats();
+    vec![
+        ("name",     trace_name(trace, index)),
+        ("optimum",  trace.final_value().map_or_else(na, |v| v.to_string())),
+        ("explored", stats.as_ref().map_or_else(na, |s| s.explored.to_string())),
+        ("time",     trace.duration().map_or_else(na, |t| t.to_string())),
+        ("gap",      stats.as_ref().map_or_else(na, |s| s.gap.to_string())),
+    ]
+}
+
+/// Replaces each `{key}` placeholder of the template with the associated
+/// value. Unknown placeholders are left as is (with a warning).
+fn fill_template(template: &str, values: &[(&str, String)]) -> String {
+    let mut result = String::new();
+    let mut rest   = template;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(len) => start + len,
+            None      => break
+        };
+        let key = &rest[start + 1..end];
+        result.push_str(&rest[..start]);
+        match values.iter().find(|(k, _)| *k == key) {
+            Some((_, value)) => result.push_str(value),
+            None => {
+                warn!("unknown placeholder {{{}}} in the template '{}'", key, template);
+                result.push_str(&rest[start..=end]);
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Plots the given traces either to the output files (when some are given)
+/// or to the terminal (as text or as a png data uri). When the index of a
+/// baseline trace is given, the other traces are plotted relative to it. A
+/// failure to save the graph to one of the output files is reported without
+/// preventing the others from being saved. Returns false when such a failure
+/// occurred.
+fn render(args: &Args, traces: &[Trace], baseline: Option<usize>, outputs: &[String], title: Option<&str>) -> bool {
+    let options = args.view_options();
+    if let Some(metric) = args.final_metric() {
+        if outputs.is_empty() && args.format != Some(Format::PngBase64) && !args.svg_stdout {
+            print_finals(traces, metric);
+            return true;
+        }
+        return show(args, &[final_view(traces, metric, &options)], outputs, title);
+    }
+    let views =
+        if args.small_multiples {
+            traces.iter().flat_map(|trace| views(args, std::slice::from_ref(trace), None, &options)).collect()
+        } else {
+            views(args, traces, baseline, &options)
+        };
+    show(args, &views, outputs, title)
+}
+
+/// The views of the given traces, as per the arguments
+fn views(args: &Args, traces: &[Trace], baseline: Option<usize>, options: &ViewOptions) -> Vec<ContinuousView> {
+    if let Some(baseline) = baseline {
+        vec![baseline_view(traces, baseline, options)]
+    } else if args.aggregate == Some(Aggregate::MinMax) {
+        vec![min_max_view(traces, options)]
+    } else if args.fringe || args.fringe_highwater {
+        vec![fringe_view(traces, options)]
+    } else if args.fringe_ratio {
+        vec![fringe_ratio_view(traces, options)]
+    } else if args.velocity.is_some() {
+        vec![velocity_view(traces, options)]
+    } else if args.lb_gain {
+        vec![lb_gain_view(traces, options)]
+    } else if args.ub_progress {
+        vec![ub_progress_view(traces, options)]
     } else {
-        let page = Page::single(&view);
-        let page = if let Some(dim) = &args.dimension {
-            page.dimensions(dim.x(), dim.y())
+        match args.gap_mode {
+            None if args.bounds_and_gap => vec![bounds_and_gap_view(traces, options)],
+            None                        => vec![bounds_view(traces, options)],
+            Some(GapMode::Absolute)     => vec![gap_view(traces, options)],
+            Some(GapMode::Relative)     => vec![relative_gap_view(traces, options)],
+            Some(GapMode::Both)         => vec![gap_view(traces, options),
+                                                relative_gap_view(traces, options)],
+        }
+    }
+}
+
+/// Saves the views to the output files (when some are given) or prints them
+/// to the terminal (as text or as a png data uri). Several views (e.g. the
+/// absolute and relative gaps) are laid out in a grid, one per cell, since
+/// plotlib would draw them on top of each other. Returns false when the graph
+/// could not be saved to one of the output files.
+fn show<V: View>(args: &Args, views: &[V], outputs: &[String], title: Option<&str>) -> bool {
+    let grid = args.small_multiples || views.len() > 1;
+    if grid && (!outputs.is_empty() || args.format == Some(Format::PngBase64)) {
+        return show_grid(args, views, outputs, title);
+    }
+    if !outputs.is_empty() {
+        let page = page(views, args.page_dimension());
+        return save_all(outputs, |path| save(&page, title, path));
+    }
+    if args.svg_stdout {
+        let svg = if grid {
+            grid_to_svg(views, args.dimension(), args.square, title)
         } else {
-            page
+            to_svg(&page(views, args.page_dimension()), title)
         };
+        let svg = svg.unwrap_or_else(|e| {
+            eprintln!("Cannot render the graph to svg: {}", e);
+            exit(1)
+        });
+        println!("{}", svg);
+        return true;
+    }
+
+    if args.format == Some(Format::PngBase64) {
+        let page = page(views, args.page_dimension());
+        let uri  = to_png_base64(&page, title).unwrap_or_else(|e| {
+            eprintln!("Cannot render the graph to png: {}", e);
+            exit(1)
+        });
+        println!("{}", uri);
+    } else {
+        if let Some(title) = title {
+            println!("{}", title);
+        }
+        for view in views.iter() {
+            let page = page(std::slice::from_ref(view), Some(args.text_dimension()));
+            println!("{}", page.to_text().expect("Cant print to text"));
+        }
+    }
+    true
+}
 
-        println!("{}", page.to_text().expect("Cant print to text"));
+/// Saves the views laid out in a grid to the output files (when some are
+/// given) or prints them as a png data uri. Returns false when the graph could
+/// not be saved to one of the output files.
+fn show_grid<V: View>(args: &Args, views: &[V], outputs: &[String], title: Option<&str>) -> bool {
+    let svg = grid_to_svg(views, args.dimension(), args.square, title).unwrap_or_else(|e| {
+        eprintln!("Cannot render the grid of views: {}", e);
+        exit(1)
+    });
+    if !outputs.is_empty() {
+        return save_all(outputs, |path| save_svg(svg.clone(), path));
     }
+    let uri = svg_to_png_base64(&svg).unwrap_or_else(|e| {
+        eprintln!("Cannot render the graph to png: {}", e);
+        exit(1)
+    });
+    println!("{}", uri);
+    true
 }
+
+/// Saves the graph to each of the output files (creating their directories if
+/// need be). A failure is reported without preventing the other files from
+/// being saved. Returns false when such a failure occurred.
+fn save_all(outputs: &[String], save: impl Fn(&Path) -> Result<(), String>) -> bool {
+    let mut saved = true;
+    for out in outputs {
+        let path   = Path::new(out);
+        let result = match path.parent() {
+            Some(dir) => create_dir_all(dir).map_err(|e| e.to_string()),
+            None      => Ok(())
+        };
+        if let Err(e) = result.and_then(|_| save(path)) {
+            eprintln!("Cannot save the graph to '{}': {}", out, e);
+            saved = false;
+        }
+    }
+    saved
+}
\ No newline at end of file
@@ -1,21 +1,313 @@
-use crate::data::Trace;
-use plotlib::repr::Plot;
-use plotlib::style::{PointStyle, PointMarker};
-use plotlib::view::ContinuousView;
+use crate::config::{Bound, FinalMetric, FinalStyle, GridLines, LogBase, Series, SeriesColor, XField};
+use crate::expr::Expr;
+use crate::data::{clamped, is_sentinel, padded, smoothed, ub_band, LogLine, Overlay, Points, Trace, UbBand};
+use plotlib::grid::Grid;
+use plotlib::repr::{BarChart, Plot};
+use plotlib::style::{BoxStyle, LineStyle, PointStyle, PointMarker};
+use plotlib::view::{CategoricalView, ContinuousView, View};
 
 pub const COLORS : [&str; 5] = [
     "#C1EBE1", "#90B9A9", "#FF0000", "#00FF00", "#0000FF"
 ];
 
+/// The size of the markers used to plot the points of a series
+pub const MARKER_SIZE : f32 = 3.0;
+
+/// The width of the lines drawn for the series rendered as lines
+const LINE_WIDTH : f32 = 1.5;
+
+/// The color of the reference lines (e.g. drawn at the known optimum)
+const REFERENCE_COLOR : &str = "#808080";
+/// The color of the points where the lower bound exceeds the upper bound
+const CROSSING_COLOR : &str = "#FF0000";
+/// The color of the reference curve overlaid from a csv file
+const OVERLAY_COLOR : &str = "#000000";
+/// The number of dashes a reference line is made of
+const REFERENCE_DASHES: usize = 25;
+/// The fraction of the x-range covered by the whisker of a final value
+const WHISKER_SPAN: f64 = 0.05;
+/// The number of explored counts at which the aggregated traces are sampled
+const AGGREGATE_SAMPLES: usize = 100;
+/// The number of explored counts at which an overlaid curve is evaluated
+const CURVE_SAMPLES: usize = 100;
+
+/// The options which tune the way the views are rendered
+#[derive(Debug, Clone)]
+pub struct ViewOptions {
+    /// The colors used to tell the traces apart
+    pub palette    : Vec<String>,
+    /// The size of the markers used to plot the points
+    pub marker_size: f32,
+    /// The label of the x-axis
+    pub x_label    : String,
+    /// The label of the y-axis (if any)
+    pub y_label    : Option<String>,
+    /// Mention the final gap and node count in the bounds legends
+    pub rich_legend: bool,
+    /// Draw the segments delimited by restart markers in different shades
+    pub segments   : bool,
+    /// Start the x-axis at zero rather than at the first explored count
+    pub x_from_zero: bool,
+    /// Only plot this bound (rather than both of them)
+    pub only       : Option<Bound>,
+    /// The colors explicitly assigned to some series (overriding the palette)
+    pub colors     : Vec<SeriesColor>,
+    /// The optimum known from another source (drawn as a reference line)
+    pub optimum    : Option<f64>,
+    /// A reference curve to overlay on the bounds
+    pub overlay    : Option<Overlay>,
+    /// A theoretical bound (function of the explored count) to overlay on the bounds
+    pub overlay_curve: Option<Expr>,
+    /// Clamp the upper bounds to this multiple of the final optimum
+    pub clamp_ub   : Option<f64>,
+    /// Plot the gaps in log scale (in this base)
+    pub log_base   : Option<LogBase>,
+    /// Mention the number of points of each series in its legend
+    pub legend_counts: bool,
+    /// Draw the bounds as staircases (they stay constant between log lines)
+    pub step       : bool,
+    /// Draw the largest fringe size reached so far instead of the fringe size
+    pub fringe_highwater: bool,
+    /// Leave the `Final` lines out of the plotted series
+    pub no_final_in_series: bool,
+    /// Draw the series in a muted shade until the first incumbent is found
+    pub two_phase  : bool,
+    /// The number of ticks on the x-axis (rather than plotlib's default)
+    pub x_ticks    : Option<usize>,
+    /// Draw the mean fringe size over this many explored count bins
+    pub bin_x      : Option<usize>,
+    /// Smooth the gap velocity with a moving average over this many points
+    pub velocity_window: Option<usize>,
+    /// Draw the final value of each trace in this style
+    pub final_style: Option<FinalStyle>,
+    /// Leave the sentinel bounds (standing for infinite bounds) out of the plot
+    pub clip       : bool,
+    /// The quantity drawn on the x-axis of the bounds view
+    pub x_field    : XField,
+    /// On a time axis, count the seconds from the first timestamp of each trace
+    pub relative_time: bool,
+    /// The factor by which the bounds (and gaps) are divided when plotted
+    pub y_scale    : f64,
+    /// The unit of the bounds once scaled (mentioned in the y label)
+    pub y_unit     : Option<String>,
+    /// Mention the solve time of each trace in a callout
+    pub show_time  : bool,
+    /// The axes along which grid lines are drawn
+    pub grid       : GridLines,
+    /// Emphasize the points where the lower bound exceeds the upper bound
+    pub highlight_crossings: bool,
+}
+impl Default for ViewOptions {
+    fn default() -> Self {
+        ViewOptions {
+            palette    : COLORS.iter().map(|c| c.to_string()).collect(),
+            marker_size: MARKER_SIZE,
+            x_label    : "Explored Nodes".to_string(),
+            y_label    : None,
+            rich_legend: false,
+            segments   : false,
+            x_from_zero: false,
+            only       : None,
+            colors     : vec![],
+            optimum    : None,
+            overlay    : None,
+            overlay_curve: None,
+            clamp_ub   : None,
+            log_base   : None,
+            legend_counts: false,
+            step       : false,
+            fringe_highwater: false,
+            no_final_in_series: false,
+            two_phase  : false,
+            x_ticks    : None,
+            bin_x      : None,
+            velocity_window: None,
+            final_style: None,
+            clip       : false,
+            x_field    : XField::Explored,
+            relative_time: false,
+            y_scale    : 1.0,
+            y_unit     : None,
+            show_time  : false,
+            grid       : GridLines::Off,
+            highlight_crossings: false,
+        }
+    }
+}
+impl ViewOptions {
+    /// The color of the given series of the i-th trace. Unless a color was
+    /// explicitly assigned to that series, it is the i-th color of the palette.
+    pub fn color(&self, trace: &Trace, i: usize, series: Series) -> &str {
+        let name = trace_name(trace, i);
+        self.colors.iter()
+            .rev()
+            .find(|c| c.series == series && c.trace == name)
+            .map_or(&self.palette[i % self.palette.len()], |c| &c.color)
+    }
+}
+
+/// The name of the trace or, when it has none, a name derived from its position
+pub fn trace_name(trace: &Trace, index: usize) -> String {
+    trace.name().map_or_else(|| format!("trace-{}", index), str::to_string)
+}
+
+/// The number of distinct shades used to tell the segments of a trace apart
+const SHADES : usize = 4;
+
+/// Lightens the given `#RRGGBB` color according to the segment it is used
+/// for. The shades cycle every `SHADES` segments; the first segment of each
+/// cycle is drawn in the given color itself.
+pub fn shade(color: &str, segment: usize) -> String {
+    let cycle   = segment % SHADES;
+    if cycle == 0 {
+        return color.to_string();
+    }
+    let level   = cycle as f64 / (SHADES + 1) as f64;
+    let channel = |i: usize| {
+        let c = color.get(i..i+2)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .unwrap_or(0) as f64;
+        (c + (255.0 - c) * level).round() as u8
+    };
+    format!("#{:02X}{:02X}{:02X}", channel(1), channel(3), channel(5))
+}
+
+/// Returns the parts of the trace which are to be drawn along with the
+/// segment they belong to (which determines their shade). Unless `segments`
+/// is set, the whole trace is drawn at once. Otherwise, each segment is drawn
+/// on its own and all segments but the first one are named after their position.
+/// When `two_phase` is set, the lines preceding the first incumbent are split
+/// off each part and drawn in the lightest shade.
+fn parts(trace: &Trace, options: &ViewOptions) -> Vec<(Trace, usize)> {
+    let parts = if options.segments {
+        trace.segments().into_iter()
+            .map(|mut part| {
+                let s = part.lines[0].segment();
+                if s > 0 {
+                    part.name = Some(match &trace.name {
+                        Some(name) => format!("{} (segment {})", name, s),
+                        None       => format!("Segment {}", s)
+                    });
+                }
+                (part, s)
+            })
+            .collect()
+    } else {
+        vec![(trace.clone(), 0)]
+    };
+    match trace.first_incumbent() {
+        Some(incumbent) if options.two_phase =>
+            parts.into_iter().flat_map(|part| phases(part, incumbent)).collect(),
+        _ => parts
+    }
+}
+
+/// Splits the part at the explored count where the first incumbent was found.
+/// The lines preceding it (if any) are named after that phase.
+fn phases((part, s): (Trace, usize), incumbent: usize) -> Vec<(Trace, usize)> {
+    let before = part.filter(|ll| ll.explored() <  incumbent);
+    let after  = part.filter(|ll| ll.explored() >= incumbent);
+    let mut phases = vec![];
+    if !before.lines.is_empty() {
+        let name = match &part.name {
+            Some(name) => format!("{} (before first incumbent)", name),
+            None       => "Before first incumbent".to_string()
+        };
+        phases.push((Trace { name: Some(name), ..before }, SHADES - 1));
+    }
+    if !after.lines.is_empty() {
+        phases.push((after, s));
+    }
+    phases
+}
+
 impl Trace {
-    pub fn lb_legend(&self) -> String {
-        self.name.as_ref().map_or("Lower Bound".to_string(), |name| {
+    pub fn lb_legend(&self, rich: bool) -> String {
+        let legend = self.name.as_ref().map_or("Lower Bound".to_string(), |name| {
             name.to_owned() + " - Lower Bound"
-        })
+        });
+        self.enrich(legend, rich)
     }
-    pub fn ub_legend(&self) -> String {
-        self.name.as_ref().map_or("Upper Bound".to_string(), |name| {
+    pub fn ub_legend(&self, rich: bool) -> String {
+        let legend = self.name.as_ref().map_or("Upper Bound".to_string(), |name| {
             name.to_owned() + " - Upper Bound"
+        });
+        self.enrich(legend, rich)
+    }
+    pub fn final_legend(&self, value: i32) -> String {
+        self.name.as_ref().map_or(format!("Final ({})", value), |name| {
+            format!("{} - Final ({})", name, value)
+        })
+    }
+    /// Draws the final value of the trace in the given style. The point and
+    /// whisker are anchored at the `Final` line, whereas the line spans the
+    /// whole x-range.
+    pub fn final_plots(&self, style: FinalStyle, (min, max): (f64, f64), color: &str, options: &ViewOptions) -> Vec<Plot> {
+        let last = match self.lines.iter().find(|ll| ll.is_final()) {
+            Some(last) => last,
+            None       => return vec![]
+        };
+        let legend = self.final_legend(last.lb());
+        let (x, y) = (x_of(options.x_field)(last), last.lb() as f64 / options.y_scale);
+        let point  = Plot::new(vec![(x, y)])
+            .legend(legend.clone())
+            .point_style(PointStyle::new().marker(PointMarker::Square).size(2.0 * options.marker_size).colour(color));
+
+        match style {
+            FinalStyle::Line    => reference_plots(y, (min, max), legend),
+            FinalStyle::Point   => vec![point],
+            FinalStyle::Whisker => {
+                let half = WHISKER_SPAN * (max - min) / 2.0;
+                let bar  = Plot::new(vec![(x - half, y), (x + half, y)])
+                    .line_style(LineStyle::new().colour(color).width(LINE_WIDTH));
+                vec![point, bar]
+            }
+        }
+    }
+    /// The callout giving the solve time reported by the summary line (if any)
+    pub fn time_legend(&self) -> Option<String> {
+        self.seconds.map(|secs| self.name.as_ref().map_or(format!("solved in {}s", secs), |name| {
+            format!("{} - solved in {}s", name, secs)
+        }))
+    }
+    /// Draws the callout giving the solve time of the trace (if known). As
+    /// plotlib cannot draw free text, the callout is the legend of a point
+    /// marking where the search ended (and the legend sits in the corner).
+    pub fn time_plot(&self, color: &str, options: &ViewOptions) -> Option<Plot> {
+        let legend = self.time_legend()?;
+        let last   = self.lines.last()?;
+        let (x, y) = (x_of(options.x_field)(last), last.lb() as f64 / options.y_scale);
+        Some(Plot::new(vec![(x, y)])
+            .legend(legend)
+            .point_style(PointStyle::new().marker(PointMarker::Circle).size(options.marker_size).colour(color)))
+    }
+    pub fn crossings_legend(&self) -> String {
+        self.name.as_ref().map_or("LB > UB".to_string(), |name| {
+            name.to_owned() + " - LB > UB"
+        })
+    }
+    /// Emphasizes the lower bound of the lines where it exceeds the upper
+    /// bound (if any), with larger red markers.
+    pub fn crossings_plot(&self, options: &ViewOptions) -> Option<Plot> {
+        let x         = x_of(options.x_field);
+        let plotted   = self.plotted(options);
+        let crossings = plotted.crossings();
+        if crossings.is_empty() {
+            return None;
+        }
+        let points = crossings.into_iter().map(|ll| (x(ll), ll.lb() as f64)).collect();
+        Some(Plot::new(y_scaled(points, options))
+            .legend(self.crossings_legend())
+            .point_style(PointStyle::new().marker(PointMarker::Circle).size(2.0 * options.marker_size).colour(CROSSING_COLOR)))
+    }
+    pub fn gap_legend(&self) -> String {
+        self.name.as_ref().map_or("Gap".to_string(), |name| {
+            name.to_owned() + " - Gap"
+        })
+    }
+    pub fn rel_gap_legend(&self) -> String {
+        self.name.as_ref().map_or("Relative Gap".to_string(), |name| {
+            name.to_owned() + " - Relative Gap"
         })
     }
     pub fn fsz_legend(&self) -> String {
@@ -23,46 +315,1047 @@ impl Trace {
             name.to_owned() + " - Frontier Size"
         })
     }
+    /// Appends the final gap and node count to the legend when `rich` is set.
+    /// Traces of searches that did not run to completion are also flagged.
+    fn enrich(&self, legend: String, rich: bool) -> String {
+        match self.stats() {
+            Some(stats) if rich && stats.proven =>
+                format!("{} (gap {}, {} nodes)", legend, stats.gap, stats.explored),
+            Some(stats) if rich =>
+                format!("{} (gap {}, {} nodes, NOT PROVEN)", legend, stats.gap, stats.explored),
+            _ => legend
+        }
+    }
 
-    pub fn lb_plot(&self, color: &str) -> Plot {
-        Plot::new(self.lb_explored())
-            .legend(self.lb_legend())
-            .point_style(PointStyle::new().marker(PointMarker::Circle).size(3.).colour(color))
+    /// The lines of this trace which are drawn in the series. Unless the
+    /// options say otherwise, these are all the lines of the trace. (The
+    /// legends and the reference lines always account for all lines.)
+    fn plotted(&self, options: &ViewOptions) -> Trace {
+        if options.no_final_in_series {
+            self.filter(LogLine::is_ongoing)
+        } else {
+            self.clone()
+        }
+    }
+    pub fn lb_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        let points = y_scaled(clipped(self.plotted(options).series(x_of(options.x_field), |ll| ll.lb() as f64), options), options);
+        bound_plot(points, self.lb_legend(options.rich_legend), PointMarker::Circle, color, options)
+    }
+    pub fn ub_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        let points = y_scaled(clipped(self.plotted(options).series(x_of(options.x_field), |ll| ll.ub() as f64), options), options);
+        bound_plot(points, self.ub_legend(options.rich_legend), PointMarker::Cross, color, options)
+    }
+    /// Plots the upper bound clamped to the given cap. The points which were
+    /// clamped are drawn at the cap, in a muted shade of the color.
+    pub fn clamped_ub_plots(&self, cap: f64, color: &str, options: &ViewOptions) -> Vec<Plot> {
+        let points          = clipped(self.plotted(options).series(x_of(options.x_field), |ll| ll.ub() as f64), options);
+        let (kept, clamped) = clamped(points, cap);
+        let (kept, clamped) = (y_scaled(kept, options), y_scaled(clamped, options));
+        let mut plots = vec![
+            bound_plot(kept, self.ub_legend(options.rich_legend), PointMarker::Cross, color, options)
+        ];
+        if !clamped.is_empty() {
+            let legend = format!("{} (clamped)", self.ub_legend(false));
+            plots.push(series_plot(clamped, legend, PointMarker::Cross, &shade(color, SHADES - 1), options));
+        }
+        plots
+    }
+    pub fn gap_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        let points = log_scale(y_scaled(self.plotted(options).gap_explored(), options), options.log_base);
+        series_plot(points, self.gap_legend(), PointMarker::Circle, color, options)
+    }
+    /// Plots the gap mapped to `offset + scale * gap`, so that it can share the
+    /// axis of the bounds
+    pub fn scaled_gap_plot(&self, offset: f64, scale: f64, color: &str, options: &ViewOptions) -> Plot {
+        let points = self.plotted(options)
+            .series(x_of(options.x_field), |ll| offset + scale * (ll.ub() as f64 - ll.lb() as f64));
+        series_plot(points, self.gap_legend(), PointMarker::Square, color, options)
+    }
+    pub fn rel_gap_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        let points = log_scale(self.plotted(options).relative_gap_explored(), options.log_base);
+        series_plot(points, self.rel_gap_legend(), PointMarker::Circle, color, options)
+    }
+    pub fn fsz_highwater_legend(&self) -> String {
+        self.name.as_ref().map_or("Frontier Size High-Water Mark".to_string(), |name| {
+            name.to_owned() + " - Frontier Size High-Water Mark"
+        })
+    }
+    /// Plots the largest fringe size reached so far as a (non decreasing) line
+    pub fn fsz_highwater_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        let points = self.plotted(options).fringe_highwater();
+        let legend = counted_legend(self.fsz_highwater_legend(), points.len(), options);
+        Plot::new(points)
+            .legend(legend)
+            .line_style(LineStyle::new().colour(color).width(LINE_WIDTH))
+    }
+    pub fn fsz_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        series_plot(self.plotted(options).fringe_explored(), self.fsz_legend(), PointMarker::Square, color, options)
+    }
+    /// Plots the mean fringe size of each of the `bins` explored count bins
+    /// as a line, which is more legible than the scatter of a long trace
+    pub fn fsz_binned_plot(&self, bins: usize, color: &str, options: &ViewOptions) -> Plot {
+        let points = self.plotted(options).binned_fringe(bins);
+        let legend = counted_legend(format!("{} (mean per bin)", self.fsz_legend()), points.len(), options);
+        Plot::new(points)
+            .legend(legend)
+            .line_style(LineStyle::new().colour(color).width(LINE_WIDTH))
+    }
+    pub fn fringe_ratio_legend(&self) -> String {
+        self.name.as_ref().map_or("Frontier Size / Explored".to_string(), |name| {
+            name.to_owned() + " - Frontier Size / Explored"
+        })
     }
-    pub fn ub_plot(&self, color: &str) -> Plot {
-        Plot::new(self.ub_explored())
-            .legend(self.ub_legend())
-            .point_style(PointStyle::new().marker(PointMarker::Cross).size(3.).colour(color))
+    pub fn fringe_ratio_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        series_plot(self.plotted(options).fringe_ratio(), self.fringe_ratio_legend(), PointMarker::Square, color, options)
     }
-    pub fn fsz_plot(&self, color: &str) -> Plot {
-        Plot::new(self.fringe_explored())
-            .legend(self.fsz_legend())
-            .point_style(PointStyle::new().marker(PointMarker::Square).size(3.).colour(color))
+    pub fn lb_gain_legend(&self) -> String {
+        self.name.as_ref().map_or("Lower Bound Gain".to_string(), |name| {
+            name.to_owned() + " - Lower Bound Gain"
+        })
+    }
+    pub fn lb_gain_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        bound_plot(self.plotted(options).cumulative_lb_gain(), self.lb_gain_legend(), PointMarker::Circle, color, options)
+    }
+    pub fn ub_progress_legend(&self) -> String {
+        self.name.as_ref().map_or("Distinct Upper Bounds".to_string(), |name| {
+            name.to_owned() + " - Distinct Upper Bounds"
+        })
+    }
+    pub fn ub_progress_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        bound_plot(self.plotted(options).distinct_ub_count(), self.ub_progress_legend(), PointMarker::Cross, color, options)
+    }
+    pub fn velocity_legend(&self) -> String {
+        self.name.as_ref().map_or("Gap Velocity".to_string(), |name| {
+            name.to_owned() + " - Gap Velocity"
+        })
+    }
+    pub fn velocity_plot(&self, color: &str, options: &ViewOptions) -> Plot {
+        let points = self.plotted(options).gap_velocity();
+        let points = match options.velocity_window {
+            Some(window) => smoothed(points, window),
+            None         => points
+        };
+        series_plot(points, self.velocity_legend(), PointMarker::Circle, color, options)
     }
 }
 
-pub fn bounds_view(traces: &[Trace]) -> ContinuousView {
-    let mut view = ContinuousView::new()
-        .x_label("Explored Nodes");
+/// Plots the points of a series with the given marker and color. When the
+/// options ask for it, the number of points is appended to the legend.
+fn series_plot(points: Points, legend: String, marker: PointMarker, color: &str, options: &ViewOptions) -> Plot {
+    let legend = counted_legend(legend, points.len(), options);
+    Plot::new(points)
+        .legend(legend)
+        .point_style(PointStyle::new().marker(marker).size(options.marker_size).colour(color))
+}
+
+/// Appends the number of points of the series to its legend when the
+/// options ask for it
+fn counted_legend(legend: String, points: usize, options: &ViewOptions) -> String {
+    if options.legend_counts {
+        format!("{} ({} pts)", legend, points)
+    } else {
+        legend
+    }
+}
+
+/// Plots the points of a bound series. Unless the options ask for a step
+/// plot, this is the same as `series_plot`. Otherwise, the bound is drawn as
+/// a staircase line (without markers).
+fn bound_plot(points: Points, legend: String, marker: PointMarker, color: &str, options: &ViewOptions) -> Plot {
+    if options.step {
+        let legend = counted_legend(legend, points.len(), options);
+        Plot::new(staircase(points))
+            .legend(legend)
+            .line_style(LineStyle::new().colour(color).width(LINE_WIDTH))
+    } else {
+        series_plot(points, legend, marker, color, options)
+    }
+}
+
+/// Inserts an intermediate point between each pair of consecutive points so
+/// that the line joining them keeps the value of the first point until the
+/// x coordinate of the second one (where it jumps to its value).
+fn staircase(points: Points) -> Points {
+    let mut steps = Vec::with_capacity(2 * points.len());
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            steps.push((point.0, points[i - 1].1));
+        }
+        steps.push(*point);
+    }
+    steps
+}
+
+/// The block characters of increasing height used to draw sparklines
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Draws the values as a one line sparkline made of at most `width` block
+/// characters. When there are more values than that, evenly spaced samples
+/// of the values are drawn.
+pub fn sparkline(values: &[f64], width: usize) -> String {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let len = values.len().min(width);
+
+    (0..len)
+        .map(|i| values[i * values.len() / len])
+        .map(|v| {
+            let level = if max > min { (v - min) / (max - min) } else { 0.0 };
+            SPARKS[(level * (SPARKS.len() - 1) as f64).round() as usize]
+        })
+        .collect()
+}
+
+/// Takes the log of the y coordinates of the points when a base is given.
+/// The points whose y coordinate is not positive are dropped since their
+/// log is undefined (e.g. the gap of a closed search).
+fn log_scale(points: Points, base: Option<LogBase>) -> Points {
+    match base {
+        Some(base) => points.into_iter()
+            .filter(|(_, y)| *y > 0.0)
+            .map(|(x, y)| (x, base.log(y)))
+            .collect(),
+        None => points
+    }
+}
+
+/// Leaves the sentinel bounds out of the series when the options ask for it
+fn clipped(points: Points, options: &ViewOptions) -> Points {
+    if options.clip {
+        points.into_iter().filter(|(_, y)| !is_sentinel(*y as i32)).collect()
+    } else {
+        points
+    }
+}
+
+/// Divides the y values by the scale of the options (the bounds are plotted in
+/// the unit of the options rather than in the unit of the trace)
+fn y_scaled(points: Points, options: &ViewOptions) -> Points {
+    if options.y_scale == 1.0 {
+        points
+    } else {
+        points.into_iter().map(|(x, y)| (x, y / options.y_scale)).collect()
+    }
+}
+
+/// The range spanned by the bounds of all the given traces (if any). Because
+/// it is computed on the traces as they are plotted, any windowing applied
+/// beforehand is accounted for. When clipping, the sentinel bounds (on either
+/// side) are not accounted for.
+fn bounds_y_range(traces: &[Trace], clip: bool) -> Option<(f64, f64)> {
+    let ranges = traces.iter()
+        .filter(|trace| !trace.lines.is_empty())
+        .filter_map(|trace| if clip { trace.clipped_bound_range() } else { Some(trace.bound_range()) });
+
+    let min = ranges.clone().map(|(min, _)| min).min()?;
+    let max = ranges.map(|(_, max)| max).max()?;
+    Some((min as f64, max as f64))
+}
+
+/// Extracts the given quantity from a log line
+fn x_of(field: XField) -> fn(&LogLine) -> f64 {
+    match field {
+        XField::Explored => |ll| ll.explored() as f64,
+        XField::Fringe   => |ll| ll.fringe() as f64,
+        XField::Index    => |ll| ll.explored() as f64,
+        XField::Time     => |ll| ll.time().unwrap_or(0.0)
+    }
+}
+
+/// The traces as they are laid out along the x-axis of the options. On an
+/// index axis, the explored count of each line is replaced by its ordinal in
+/// the trace (1, 2, 3, ...), so that it is the one read by `x_of`. On a time
+/// axis, the lines which have no timestamp are left out and, when the time is
+/// relative, the first timestamp of each trace is subtracted from all others.
+/// The traces are left as they are on the other axes.
+fn on_x_axis(traces: &[Trace], options: &ViewOptions) -> Vec<Trace> {
+    match options.x_field {
+        XField::Index => traces.iter().map(indexed).collect(),
+        XField::Time  => traces.iter().map(|trace| timed(trace, options.relative_time)).collect(),
+        _             => traces.to_vec()
+    }
+}
+fn timed(trace: &Trace, relative: bool) -> Trace {
+    let mut trace = trace.filter(|ll| ll.time().is_some());
+    let origin    = trace.lines.first().and_then(LogLine::time).filter(|_| relative).unwrap_or(0.0);
+    for line in trace.lines.iter_mut() {
+        match line {
+            LogLine::Ongoing {time, ..} => *time = time.map(|t| t - origin),
+            LogLine::Final   {time, ..} => *time = time.map(|t| t - origin)
+        }
+    }
+    trace
+}
+fn indexed(trace: &Trace) -> Trace {
+    let mut trace = trace.clone();
+    for (i, line) in trace.lines.iter_mut().enumerate() {
+        match line {
+            LogLine::Ongoing {explored, ..} => *explored = i + 1,
+            LogLine::Final   {explored, ..} => *explored = i + 1
+        }
+    }
+    trace
+}
+
+/// The range of explored counts covered by the view of the given traces
+fn explored_range(traces: &[Trace], options: &ViewOptions) -> Option<(f64, f64)> {
+    x_range(traces, XField::Explored, options)
+}
+
+/// The range of values of the given field covered by the view of the traces
+fn x_range(traces: &[Trace], field: XField, options: &ViewOptions) -> Option<(f64, f64)> {
+    let x      = x_of(field);
+    let values = traces.iter()
+        .flat_map(|trace| trace.lines.iter())
+        .map(x);
+
+    let min = if options.x_from_zero { 0.0 } else { values.clone().reduce(f64::min)? };
+    let max = values.reduce(f64::max)?;
+    Some((min, max))
+}
+
+/// Draws a horizontal dashed reference line at the given y across the x-range.
+/// Because plotlib has no dashed line style, each dash is a plot of its own
+/// (only the first one of which shows up in the legend).
+fn reference_plots(y: f64, (min, max): (f64, f64), legend: String) -> Vec<Plot> {
+    let step = (max - min) / REFERENCE_DASHES as f64;
+    let mut legend = Some(legend);
+    (0..REFERENCE_DASHES)
+        .map(|i| {
+            let start = min + i as f64 * step;
+            let dash  = Plot::new(vec![(start, y), (start + step / 2.0, y)])
+                .line_style(LineStyle::new().colour(REFERENCE_COLOR).width(1.0));
+            match legend.take() {
+                Some(legend) => dash.legend(legend),
+                None         => dash
+            }
+        })
+        .collect()
+}
+
+/// Draws the reference curve as a plain line, to tell it apart from the
+/// series of the traces which are drawn as points.
+fn overlay_plot(overlay: &Overlay, options: &ViewOptions) -> Plot {
+    Plot::new(y_scaled(overlay.points.clone(), options))
+        .legend(overlay.name.clone())
+        .line_style(LineStyle::new().colour(OVERLAY_COLOR).width(LINE_WIDTH))
+}
+
+/// The label of a y-axis showing the given quantity, with its unit (if any)
+fn unit_label(quantity: &str, options: &ViewOptions) -> String {
+    options.y_unit.as_ref().map_or(quantity.to_string(), |unit| format!("{} ({})", quantity, unit))
+}
+
+/// The label of the y-axis of a gap view
+fn gap_label(quantity: &str, options: &ViewOptions) -> String {
+    options.log_base.map_or(quantity.to_string(), |base| base.label(quantity))
+}
+
+/// Creates an empty view with the x-axis configured as per the options
+fn explored_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    x_view(traces, XField::Explored, options)
+}
+
+/// Creates an empty view whose x-axis shows the given field. The label of the
+/// explored counts axis is the configured one.
+fn x_view(traces: &[Trace], field: XField, options: &ViewOptions) -> ContinuousView {
+    let label = match field {
+        XField::Explored => options.x_label.as_str(),
+        _                => field.label()
+    };
+    let view = ContinuousView::new()
+        .x_label(label);
+    let view = match &options.y_label {
+        Some(label) => view.y_label(label.as_str()),
+        None        => view
+    };
+
+    let mut view = match (x_range(traces, field, options), options.x_ticks) {
+        (Some((min, max)), Some(ticks)) => {
+            let (min, max) = nice_range(min, max, ticks);
+            view.x_range(min, max).x_max_ticks(ticks)
+        },
+        (Some((min, max)), None) if options.x_from_zero => view.x_range(min, max),
+        _                                               => view
+    };
+    if let Some(grid) = grid(options) {
+        view.add_grid(grid);
+    }
+    view
+}
+
+/// The number of grid lines drawn along an axis of the grid
+const GRID_LINES : u32 = 6;
+
+/// The grid of the views, if any. Plotlib always draws the lines along both
+/// axes: along the axis which should have none, only the two lines lying on
+/// the borders of the frame are drawn.
+fn grid(options: &ViewOptions) -> Option<Grid> {
+    let along = options.x_ticks.map_or(GRID_LINES, |ticks| ticks as u32 + 1);
+    match options.grid {
+        GridLines::Off  => None,
+        GridLines::X    => Some(Grid::new(along, 2)),
+        GridLines::Y    => Some(Grid::new(2, GRID_LINES)),
+        GridLines::Both => Some(Grid::new(along, GRID_LINES))
+    }
+}
+
+/// Extends the range to the nearest multiples of a round step (1, 2 or 5
+/// times a power of ten) which cuts it in at most `ticks` intervals.
+fn nice_range(min: f64, max: f64, ticks: usize) -> (f64, f64) {
+    let raw = (max - min) / ticks.max(1) as f64;
+    if raw <= 0.0 {
+        return (min, max);
+    }
+    let magnitude = 10_f64.powf(raw.log10().floor());
+    let step      = [1.0, 2.0, 5.0, 10.0].iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude);
+    ((min / step).floor() * step, (max / step).ceil() * step)
+}
+
+/// The theoretical bound given by `--overlay-curve`, sampled over the
+/// explored counts of the traces
+fn curve_overlay(traces: &[Trace], options: &ViewOptions) -> Option<Overlay> {
+    options.overlay_curve.as_ref()
+        .zip(explored_range(traces, options))
+        .map(|(expr, range)| Overlay { name: expr.to_string(), points: expr.sample(range, CURVE_SAMPLES) })
+}
+
+/// The cap of the upper bound of each trace (if it is clamped)
+fn ub_caps(traces: &[Trace], options: &ViewOptions) -> Vec<Option<f64>> {
+    traces.iter()
+        .map(|trace| options.clamp_ub.and_then(|factor| trace.ub_cap(factor)))
+        .collect()
+}
+
+/// The (padded) range of the y-axis of the bounds view. It covers the bounds
+/// (up to their caps) along with the optimum and the overlays.
+fn bounds_axis_range(traces: &[Trace], options: &ViewOptions) -> Option<(f64, f64)> {
+    let curve      = curve_overlay(traces, options);
+    let references = options.optimum.into_iter()
+        .chain(options.overlay.iter().chain(curve.iter()).flat_map(|o| o.points.iter().map(|(_, y)| *y)));
+    let bounds = match ub_caps(traces, options).into_iter().collect::<Option<Vec<f64>>>() {
+        Some(caps) if !caps.is_empty() => {
+            let cap = caps.into_iter().fold(f64::NEG_INFINITY, f64::max);
+            bounds_y_range(traces, options.clip).map(|(min, max)| (min, max.min(cap)))
+        },
+        _ => bounds_y_range(traces, options.clip)
+    };
+    references
+        .fold(bounds, |range, y| range.map(|(min, max)| (min.min(y), max.max(y))))
+        .map(|(min, max)| padded(min / options.y_scale, max / options.y_scale))
+}
+
+pub fn bounds_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let traces   = &on_x_axis(traces, options)[..];
+    let mut view = x_view(traces, options.x_field, options);
+    if options.y_label.is_none() && options.y_unit.is_some() {
+        view = view.y_label(unit_label("Bounds", options).as_str());
+    }
+
+    let curve    = curve_overlay(traces, options);
+    let overlays = options.overlay.iter().chain(curve.iter());
+    let caps     = ub_caps(traces, options);
+    if let Some((min, max)) = bounds_axis_range(traces, options) {
+        view = view.y_range(min, max);
+    }
+
+    for (i, trace) in traces.iter().enumerate() {
+        let lb_color = options.color(trace, i, Series::LowerBound);
+        let ub_color = options.color(trace, i, Series::UpperBound);
+        for (part, s) in parts(trace, options) {
+            if options.only != Some(Bound::Upper) {
+                view = view.add(part.lb_plot(&shade(lb_color, s), options));
+            }
+            if options.only != Some(Bound::Lower) {
+                match caps[i] {
+                    Some(cap) =>
+                        for plot in part.clamped_ub_plots(cap, &shade(ub_color, s), options) {
+                            view = view.add(plot);
+                        },
+                    None =>
+                        view = view.add(part.ub_plot(&shade(ub_color, s), options))
+                }
+            }
+        }
+    }
+
+    for overlay in overlays {
+        view = view.add(overlay_plot(overlay, options));
+    }
+    if let (Some(optimum), Some(range)) = (options.optimum, x_range(traces, options.x_field, options)) {
+        for dash in reference_plots(optimum / options.y_scale, range, format!("Optimum ({})", optimum)) {
+            view = view.add(dash);
+        }
+    }
+    if let (Some(style), Some(range)) = (options.final_style, x_range(traces, options.x_field, options)) {
+        for (i, trace) in traces.iter().enumerate() {
+            let color = options.color(trace, i, Series::UpperBound);
+            for plot in trace.final_plots(style, range, color, options) {
+                view = view.add(plot);
+            }
+        }
+    }
+    if options.show_time {
+        for (i, trace) in traces.iter().enumerate() {
+            if let Some(plot) = trace.time_plot(options.color(trace, i, Series::LowerBound), options) {
+                view = view.add(plot);
+            }
+        }
+    }
+    if options.highlight_crossings {
+        for plot in traces.iter().filter_map(|trace| trace.crossings_plot(options)) {
+            view = view.add(plot);
+        }
+    }
+
+    view
+}
+/// Draws the bounds along with the gap between them. Because plotlib has no
+/// secondary y-axis, the gap is rescaled so that zero lies at the bottom of the
+/// bounds axis and the largest gap at its top; the y label gives that scale.
+pub fn bounds_and_gap_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = bounds_view(traces, options);
+    let traces   = &on_x_axis(traces, options)[..];
+
+    let max_gap = traces.iter()
+        .flat_map(|trace| trace.gap_explored())
+        .map(|(_, gap)| gap)
+        .fold(0.0, f64::max);
+    let range   = bounds_axis_range(traces, options).filter(|_| max_gap > 0.0);
+    let label   = match range {
+        Some(_) => format!("{} (Gap: 0 at the bottom, {} at the top)", unit_label("LB / UB", options), max_gap / options.y_scale),
+        None    => unit_label("LB / UB", options)
+    };
+    view = view.y_label(options.y_label.as_deref().unwrap_or(&label));
+
+    if let Some((min, max)) = range {
+        let scale = (max - min) / max_gap;
+        for (i, trace) in traces.iter().enumerate() {
+            let color = options.color(trace, i, Series::Gap);
+            for (part, s) in parts(trace, options) {
+                view = view.add(part.scaled_gap_plot(min, scale, &shade(color, s), options));
+            }
+        }
+    }
+
+    view
+}
+pub fn fringe_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = explored_view(traces, options);
+
+    let range = traces.iter()
+        .filter(|trace| !trace.lines.is_empty())
+        .map(Trace::padded_fringe_range)
+        .reduce(|(min1, max1), (min2, max2)| (min1.min(min2), max1.max(max2)));
+    if let Some((min, max)) = range {
+        view = view.y_range(min, max);
+    }
+
+    for (i, trace) in traces.iter().enumerate() {
+        let color = options.color(trace, i, Series::Fringe);
+        for (part, s) in parts(trace, options) {
+            let plot = if options.fringe_highwater {
+                part.fsz_highwater_plot(&shade(color, s), options)
+            } else if let Some(bins) = options.bin_x {
+                part.fsz_binned_plot(bins, &shade(color, s), options)
+            } else {
+                part.fsz_plot(&shade(color, s), options)
+            };
+            view = view.add(plot);
+        }
+    }
+
+    view
+}
+/// Plots the ratio of the fringe size to the explored count of each trace.
+/// A rising ratio means the search broadens faster than it closes.
+pub fn fringe_ratio_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = explored_view(traces, options)
+        .y_label("Frontier Size / Explored");
+
+    for (i, trace) in traces.iter().enumerate() {
+        let color = options.color(trace, i, Series::Fringe);
+        for (part, s) in parts(trace, options) {
+            view = view
+                .add(part.fringe_ratio_plot(&shade(color, s), options));
+        }
+    }
+
+    view
+}
+/// Plots the total improvement of the lower bound of each trace accumulated
+/// over the run. Unlike the lower bound itself, this curve only goes up.
+pub fn lb_gain_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = explored_view(traces, options)
+        .y_label("Cumulative Lower Bound Gain");
+
+    for (i, trace) in traces.iter().enumerate() {
+        let color = options.color(trace, i, Series::LowerBound);
+        for (part, s) in parts(trace, options) {
+            view = view
+                .add(part.lb_gain_plot(&shade(color, s), options));
+        }
+    }
+
+    view
+}
+/// Plots the number of distinct upper bounds seen so far by each trace. The
+/// flat regions of this staircase are the stalls of the upper bound.
+pub fn ub_progress_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = explored_view(traces, options)
+        .y_label("Distinct Upper Bounds");
+
+    for (i, trace) in traces.iter().enumerate() {
+        let color = options.color(trace, i, Series::UpperBound);
+        for (part, s) in parts(trace, options) {
+            view = view
+                .add(part.ub_progress_plot(&shade(color, s), options));
+        }
+    }
+
+    view
+}
+/// Plots the rate at which the gap of each trace closes (its derivative with
+/// respect to the explored count). A long flat stretch near zero is a stall.
+pub fn velocity_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = explored_view(traces, options)
+        .y_label("Gap Velocity (per explored node)");
+
+    for (i, trace) in traces.iter().enumerate() {
+        let color = options.color(trace, i, Series::Gap);
+        for (part, s) in parts(trace, options) {
+            view = view
+                .add(part.velocity_plot(&shade(color, s), options));
+        }
+    }
+
+    view
+}
+pub fn gap_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = explored_view(traces, options)
+        .y_label(gap_label(&unit_label("Gap", options), options).as_str());
+
+    for (i, trace) in traces.iter().enumerate() {
+        let color = options.color(trace, i, Series::Gap);
+        for (part, s) in parts(trace, options) {
+            view = view
+                .add(part.gap_plot(&shade(color, s), options));
+        }
+    }
+
+    view
+}
+pub fn relative_gap_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = explored_view(traces, options)
+        .y_label(gap_label("Relative Gap", options).as_str());
 
     for (i, trace) in traces.iter().enumerate() {
-        let color = COLORS[i % COLORS.len()];
-        view = view
-            .add(trace.lb_plot(color))
-            .add(trace.ub_plot(color));
+        let color = options.color(trace, i, Series::RelativeGap);
+        for (part, s) in parts(trace, options) {
+            view = view
+                .add(part.rel_gap_plot(&shade(color, s), options));
+        }
     }
 
     view
 }
-pub fn fringe_view(traces: &[Trace]) -> ContinuousView {
-    let mut view = ContinuousView::new()
-        .x_label("Explored Nodes");
+/// Aggregates the upper bounds of all traces (e.g. repeated runs of the same
+/// instance) into the band between their minimum and maximum, along with
+/// their median. Because plotlib cannot fill an area, the band is shaded by
+/// a line zigzagging between its bounds at each sampled explored count.
+pub fn min_max_view(traces: &[Trace], options: &ViewOptions) -> ContinuousView {
+    let mut view = explored_view(traces, options);
+    let band     = ub_band(traces, AGGREGATE_SAMPLES);
+    if band.median.is_empty() {
+        return view;
+    }
+
+    let min = band.min.iter().map(|(_, y)| *y).fold(f64::INFINITY, f64::min);
+    let max = band.max.iter().map(|(_, y)| *y).fold(f64::NEG_INFINITY, f64::max);
+    let (min, max) = padded(min, max);
+    view = view.y_range(min, max);
+
+    // The points are marked as well as joined: the terminal only draws points
+    let color  = options.color(&traces[0], 0, Series::UpperBound);
+    let muted  = shade(color, SHADES - 1);
+    let legend = format!("Upper Bound (min-max of {} runs)", traces.len());
+    view.add(Plot::new(band_zigzag(&band))
+            .legend(legend)
+            .line_style(LineStyle::new().colour(muted.as_str()).width(1.0))
+            .point_style(PointStyle::new().marker(PointMarker::Cross).size(options.marker_size / 2.0).colour(muted.as_str())))
+        .add(Plot::new(band.median)
+            .legend("Upper Bound (median)".to_string())
+            .line_style(LineStyle::new().colour(color).width(LINE_WIDTH))
+            .point_style(PointStyle::new().marker(PointMarker::Circle).size(options.marker_size / 2.0).colour(color)))
+}
+
+/// The points of a line going back and forth between the minimum and the
+/// maximum of the band at each of its explored counts
+fn band_zigzag(band: &UbBand) -> Points {
+    band.min.iter().zip(band.max.iter())
+        .enumerate()
+        .flat_map(|(i, (lo, hi))| if i % 2 == 0 { [*lo, *hi] } else { [*hi, *lo] })
+        .collect()
+}
+/// The final number of the trace which is compared by `final_view`: its
+/// optimum (None when it has no `Final` line) or the gap at its last line.
+pub fn final_metric(trace: &Trace, metric: FinalMetric) -> Option<f64> {
+    match metric {
+        FinalMetric::Value => trace.final_value().map(f64::from),
+        FinalMetric::Gap   => trace.stats().map(|stats| stats.gap as f64)
+    }
+}
+/// Compares the final value (or gap) of the traces in a bar chart, with one
+/// bar per trace labeled by its name. The traces which have no such value
+/// are left out.
+pub fn final_view(traces: &[Trace], metric: FinalMetric, options: &ViewOptions) -> CategoricalView {
+    let label    = match metric {
+        FinalMetric::Value => "Final Value",
+        FinalMetric::Gap   => "Final Gap"
+    };
+    let mut view = CategoricalView::new()
+        .x_label("Trace")
+        .y_label(options.y_label.as_deref().unwrap_or(label));
 
     for (i, trace) in traces.iter().enumerate() {
-        let color = COLORS[i % COLORS.len()];
-        view = view
-            .add(trace.fsz_plot(color));
+        if let Some(value) = final_metric(trace, metric) {
+            let color = options.color(trace, i, Series::UpperBound);
+            view = view.add(BarChart::new(value)
+                .label(trace_name(trace, i))
+                .style(&BoxStyle::new().fill(color)));
+        }
+    }
+
+    view
+}
+/// Plots the upper bound of each trace as a ratio to the upper bound of the
+/// baseline trace (at the same explored count). The baseline itself is not
+/// plotted but a reference line is drawn at 1.
+pub fn baseline_view(traces: &[Trace], baseline: usize, options: &ViewOptions) -> ContinuousView {
+    let base     = &traces[baseline];
+    let mut view = explored_view(traces, options)
+        .y_label(format!("UB / {} UB", trace_name(base, baseline)).as_str());
+
+    for (i, trace) in traces.iter().enumerate().filter(|(i, _)| *i != baseline) {
+        let color  = options.color(trace, i, Series::UpperBound);
+        let legend = format!("{} / {}", trace.ub_legend(false), trace_name(base, baseline));
+        view = view.add(series_plot(trace.ub_ratio(base), legend, PointMarker::Cross, color, options));
+    }
+    if let Some(range) = explored_range(traces, options) {
+        for dash in reference_plots(1.0, range, trace_name(base, baseline)) {
+            view = view.add(dash);
+        }
     }
 
     view
+}
+
+#[cfg(test)]
+mod test {
+    use plotlib::page::Page;
+    use plotlib::view::{ContinuousView, View};
+
+    use crate::data::{padded, Trace, UbBand};
+    use crate::config::{FinalMetric, FinalStyle, GridLines, LogBase, XField};
+    use crate::repr::{band_zigzag, bounds_and_gap_view, bounds_view, final_metric, bounds_y_range, fringe_view, gap_view, log_scale, min_max_view, nice_range,
+                      parts, reference_plots, x_range, bounds_axis_range, sparkline, staircase, on_x_axis, unit_label, ViewOptions, REFERENCE_DASHES, SHADES};
+
+    /// The characters used to draw the markers of the points in text
+    const MARKS: [char; 3] = ['●', '×', '■'];
+
+    /// The fixed trace rendered by the render tests
+    fn fixture() -> Trace {
+        Trace::from("
+Explored 100, LB 0, UB 40, Fringe sz 10
+Explored 200, LB 5, UB 30, Fringe sz 30
+Explored 300, LB 8, UB 20, Fringe sz 20
+Explored 400, LB 10, UB 15, Fringe sz 5
+Final 12, Explored 500
+")
+    }
+    /// Renders the view as text (as is done in the terminal) and checks that
+    /// the output mentions all the expected labels and that at least
+    /// `min_marks` points were actually drawn.
+    fn assert_renders(view: &ContinuousView, labels: &[&str], min_marks: usize) {
+        let text  = Page::single(view).dimensions(80, 30).to_text().expect("Cannot render to text");
+        let marks = text.chars().filter(|c| MARKS.contains(c)).count();
+
+        for label in labels {
+            assert!(text.contains(label), "'{}' is missing from the rendering:\n{}", label, text);
+        }
+        assert!(marks >= min_marks, "only {} points were drawn:\n{}", marks, text);
+    }
+
+    #[test]
+    fn y_range_only_accounts_for_the_visible_window() {
+        let log   = "
+Explored 100, LB -500, UB 1000, Fringe sz 10
+Explored 200, LB 0, UB 300, Fringe sz 20
+Explored 300, LB 9, UB 14, Fringe sz 30
+Explored 400, LB 10, UB 12, Fringe sz 30
+Final 11, Explored 500
+";
+        let trace  = Trace::from(log);
+        let tail   = trace.window(Some(300), None);
+
+        assert_eq!(Some((-500.0, 1000.0)), bounds_y_range(&[trace], false));
+        assert_eq!(Some((9.0, 14.0)),      bounds_y_range(&[tail], false));
+    }
+    #[test]
+    fn y_range_of_empty_traces() {
+        assert_eq!(None, bounds_y_range(&[], false));
+        assert_eq!(None, bounds_y_range(&[Trace::from("")], false));
+    }
+    #[test]
+    fn reference_dashes_span_the_x_range() {
+        let dashes = reference_plots(42.0, (100.0, 600.0), "Optimum".to_string());
+        assert_eq!(REFERENCE_DASHES, dashes.len());
+
+        assert_eq!(vec![(100.0, 42.0), (110.0, 42.0)], dashes.first().unwrap().data);
+        assert_eq!(vec![(580.0, 42.0), (590.0, 42.0)], dashes.last().unwrap().data);
+        assert!(dashes[0].legend.is_some());
+        assert!(dashes[1..].iter().all(|dash| dash.legend.is_none()));
+    }
+    #[test]
+    fn sparkline_maps_values_to_block_levels() {
+        assert_eq!("█▅▃▁", sparkline(&[70.0, 40.0, 30.0, 10.0], 10));
+        assert_eq!("▁▁▁",  sparkline(&[5.0, 5.0, 5.0], 10));
+        assert_eq!("",     sparkline(&[], 10));
+    }
+    #[test]
+    fn sparkline_samples_long_series() {
+        let values = (0..100).map(|i| i as f64).collect::<Vec<f64>>();
+        assert_eq!("▁▂▄▅▇", sparkline(&values, 5));
+    }
+    #[test]
+    fn log_scale_drops_the_null_gaps() {
+        let points = vec![(1.0, 100.0), (2.0, 10.0), (3.0, 0.0)];
+
+        assert_eq!(vec![(1.0, 2.0), (2.0, 1.0)], log_scale(points.clone(), Some(LogBase::Ten)));
+        assert_eq!(points.clone(),               log_scale(points, None));
+    }
+    #[test]
+    fn legend_counts_the_points_of_the_series() {
+        let trace   = Trace::from("
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 20
+Final 10, Explored 300
+");
+        let options = ViewOptions { legend_counts: true, ..ViewOptions::default() };
+
+        assert_eq!(Some("Upper Bound (3 pts)".to_string()), trace.ub_plot("#000000", &options).legend);
+        assert_eq!(Some("Upper Bound".to_string()), trace.ub_plot("#000000", &ViewOptions::default()).legend);
+    }
+    #[test]
+    fn staircase_keeps_the_bound_until_the_next_point() {
+        let points = vec![(100.0, 30.0), (200.0, 20.0), (300.0, 10.0)];
+
+        assert_eq!(vec![(100.0, 30.0), (200.0, 30.0), (200.0, 20.0), (300.0, 20.0), (300.0, 10.0)],
+                   staircase(points));
+        assert!(staircase(vec![]).is_empty());
+    }
+    #[test]
+    fn final_line_can_be_left_out_of_the_series() {
+        let trace   = Trace::from("
+Explored 100, LB 0, UB 30, Fringe sz 10
+Explored 200, LB 5, UB 20, Fringe sz 20
+Final 10, Explored 300
+");
+        let options = ViewOptions { no_final_in_series: true, rich_legend: true, ..ViewOptions::default() };
+        let plot    = trace.ub_plot("#000000", &options);
+
+        assert_eq!(vec![(100.0, 30.0), (200.0, 20.0)], plot.data);
+        assert_eq!(Some("Upper Bound (gap 0, 300 nodes)".to_string()), plot.legend);
+    }
+    #[test]
+    fn render_bounds_view() {
+        let view = bounds_view(&[fixture()], &ViewOptions::default());
+        assert_renders(&view, &["Explored Nodes"], 5);
+    }
+    #[test]
+    fn render_gap_view() {
+        let view = gap_view(&[fixture()], &ViewOptions::default());
+        assert_renders(&view, &["Explored Nodes", "Gap"], 3);
+    }
+    #[test]
+    fn render_fringe_view() {
+        let view = fringe_view(&[fixture()], &ViewOptions::default());
+        assert_renders(&view, &["Explored Nodes"], 3);
+    }
+    #[test]
+    fn two_phase_splits_the_series_at_the_first_incumbent() {
+        let options = ViewOptions { two_phase: true, ..ViewOptions::default() };
+        let split   = parts(&fixture(), &options);
+
+        assert_eq!(2, split.len());
+        assert_eq!(vec![100], split[0].0.lines.iter().map(|ll| ll.explored()).collect::<Vec<_>>());
+        assert_eq!(Some("Before first incumbent".to_string()), split[0].0.name);
+        assert_eq!(SHADES - 1, split[0].1);
+        assert_eq!(4, split[1].0.lines.len());
+        assert_eq!(0, split[1].1);
+
+        assert_eq!(1, parts(&fixture(), &ViewOptions::default()).len());
+    }
+    #[test]
+    fn nice_range_ends_on_round_ticks() {
+        assert_eq!((0.0, 7_000_000.0), nice_range(0.0, 6_790_000.0, 10));
+        assert_eq!((0.0, 1000.0),      nice_range(130.0, 980.0, 5));
+        assert_eq!((100.0, 500.0),     nice_range(100.0, 500.0, 4));
+        assert_eq!((42.0, 42.0),       nice_range(42.0, 42.0, 4));
+    }
+    #[test]
+    fn band_zigzag_alternates_between_the_bounds() {
+        let band = UbBand {
+            min   : vec![(1.0, 10.0), (2.0, 5.0), (3.0, 1.0)],
+            median: vec![(1.0, 15.0), (2.0, 7.0), (3.0, 2.0)],
+            max   : vec![(1.0, 20.0), (2.0, 9.0), (3.0, 3.0)]
+        };
+        assert_eq!(vec![(1.0, 10.0), (1.0, 20.0), (2.0, 9.0), (2.0, 5.0), (3.0, 1.0), (3.0, 3.0)],
+                   band_zigzag(&band));
+    }
+    #[test]
+    fn min_max_view_renders() {
+        let other = Trace::from("
+Explored 100, LB 0, UB 50, Fringe sz 10
+Explored 300, LB 8, UB 25, Fringe sz 20
+Explored 500, LB 10, UB 12, Fringe sz 5
+");
+        let view  = min_max_view(&[fixture(), other], &ViewOptions::default());
+        // The median and the band are sampled at 100 explored counts across the
+        // width of the rendering
+        assert_renders(&view, &["Explored Nodes"], 50);
+    }
+    #[test]
+    fn final_metric_of_the_traces() {
+        let unproven = Trace::from("Explored 100, LB 5, UB 30, Fringe sz 10");
+
+        assert_eq!(Some(12.0), final_metric(&fixture(), FinalMetric::Value));
+        assert_eq!(Some(0.0),  final_metric(&fixture(), FinalMetric::Gap));
+        assert_eq!(None,       final_metric(&unproven,  FinalMetric::Value));
+        assert_eq!(Some(25.0), final_metric(&unproven,  FinalMetric::Gap));
+    }
+    #[test]
+    fn bounds_against_the_fringe_size() {
+        let options = ViewOptions { x_field: XField::Fringe, ..ViewOptions::default() };
+        let lb      = fixture().lb_plot("red", &options);
+
+        assert_eq!(Some((0.0, 30.0)), x_range(&[fixture()], XField::Fringe, &options));
+        assert_eq!((10.0, 0.0), lb.data[0]);
+        assert_renders(&bounds_view(&[fixture()], &options), &["Frontier Size"], 8);
+    }
+    #[test]
+    fn gap_is_rescaled_to_the_bounds_axis() {
+        let options = ViewOptions::default();
+        let view    = bounds_and_gap_view(&[fixture()], &options);
+        assert_renders(&view, &["LB / UB (Gap: 0 at the bottom, 40 at the top)"], 12);
+
+        let (min, max) = bounds_axis_range(&[fixture()], &options).unwrap();
+        let gap        = fixture().scaled_gap_plot(min, (max - min) / 40.0, "red", &options);
+        assert_eq!((100.0, max), gap.data[0]);
+        assert_eq!((500.0, min), gap.data[4]);
+    }
+    #[test]
+    fn final_value_styles() {
+        let options = ViewOptions::default();
+        let line    = fixture().final_plots(FinalStyle::Line, (0.0, 1000.0), "red", &options);
+        let point   = fixture().final_plots(FinalStyle::Point, (0.0, 1000.0), "red", &options);
+        let whisker = fixture().final_plots(FinalStyle::Whisker, (0.0, 1000.0), "red", &options);
+
+        assert_eq!(REFERENCE_DASHES, line.len());
+        assert_eq!(vec![(500.0, 12.0)], point[0].data);
+        assert_eq!(vec![(475.0, 12.0), (525.0, 12.0)], whisker[1].data);
+        assert!(Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10")
+            .final_plots(FinalStyle::Point, (0.0, 1000.0), "red", &options).is_empty());
+
+        let view = bounds_view(&[fixture()], &ViewOptions { final_style: Some(FinalStyle::Whisker), ..options });
+        assert_renders(&view, &["Final (12)"], 9);
+    }
+    #[test]
+    fn clipping_leaves_the_sentinels_out() {
+        let trace = Trace::from("
+Explored 100, LB -2147483648, UB 2147483647, Fringe sz 10
+Explored 200, LB 5, UB 30, Fringe sz 30
+Explored 300, LB 8, UB 20, Fringe sz 20
+");
+        let options = ViewOptions { clip: true, ..ViewOptions::default() };
+
+        assert_eq!(Some((-2147483648.0, 2147483647.0)), bounds_y_range(std::slice::from_ref(&trace), false));
+        assert_eq!(Some((5.0, 30.0)), bounds_y_range(std::slice::from_ref(&trace), true));
+        assert_eq!(vec![(200.0, 5.0), (300.0, 8.0)], trace.lb_plot("red", &options).data);
+        assert_eq!(3, trace.ub_plot("red", &ViewOptions::default()).data.len());
+    }
+    #[test]
+    fn y_scale_divides_the_bounds() {
+        let options = ViewOptions { y_scale: 100.0, y_unit: Some("k$".to_string()), ..ViewOptions::default() };
+
+        assert_eq!(vec![(100.0, 0.0), (200.0, 0.05), (300.0, 0.08), (400.0, 0.1), (500.0, 0.12)],
+                   fixture().lb_plot("red", &options).data);
+        assert_eq!((100.0, 0.4), fixture().gap_plot("red", &options).data[0]);
+        assert_eq!(vec![(500.0, 0.12)], fixture().final_plots(FinalStyle::Point, (0.0, 1000.0), "red", &options)[0].data);
+        assert_eq!(Some(padded(0.0, 0.4)), bounds_axis_range(&[fixture()], &options));
+        assert_eq!("Bounds (k$)", unit_label("Bounds", &options));
+        assert_eq!("Gap", unit_label("Gap", &ViewOptions::default()));
+    }
+    #[test]
+    fn index_axis_uses_the_line_ordinals() {
+        let options = ViewOptions { x_field: XField::Index, ..ViewOptions::default() };
+        let traces  = on_x_axis(&[fixture()], &options);
+
+        assert_eq!(vec![(1.0, 0.0), (2.0, 5.0), (3.0, 8.0), (4.0, 10.0), (5.0, 12.0)], traces[0].lb_plot("red", &options).data);
+        assert_eq!(Some((1.0, 5.0)), x_range(&traces, XField::Index, &options));
+        assert_eq!(100, on_x_axis(&[fixture()], &ViewOptions::default())[0].lines[0].explored());
+        assert_renders(&bounds_view(&[fixture()], &options), &["Iteration"], 8);
+    }
+    #[test]
+    fn time_callout_mentions_the_solve_time() {
+        let mut trace = fixture();
+        trace.seconds = Some(5.04);
+        let options   = ViewOptions { show_time: true, ..ViewOptions::default() };
+
+        assert_eq!(None, fixture().time_legend());
+        assert_eq!(Some("solved in 5.04s".to_string()), trace.time_legend());
+        assert_eq!(vec![(500.0, 12.0)], trace.time_plot("red", &options).unwrap().data);
+
+        let named = trace.with_name("a");
+        assert_renders(&bounds_view(&[named], &options), &["a - solved in 5.04s"], 9);
+    }
+    #[test]
+    fn relative_time_axis_starts_at_the_first_timestamp() {
+        let trace = Trace::from("
+Explored 50, LB 0, UB 50, Fringe sz 10
+[10.5s] Explored 100, LB 0, UB 40, Fringe sz 10
+[11.0s] Explored 200, LB 5, UB 30, Fringe sz 30
+[12.5s] Final 12, Explored 500
+");
+        let absolute = ViewOptions { x_field: XField::Time, ..ViewOptions::default() };
+        let relative = ViewOptions { relative_time: true, ..absolute.clone() };
+
+        assert_eq!(vec![(10.5, 0.0), (11.0, 5.0), (12.5, 12.0)], on_x_axis(std::slice::from_ref(&trace), &absolute)[0].lb_plot("red", &absolute).data);
+        assert_eq!(vec![(0.0, 0.0), (0.5, 5.0), (2.0, 12.0)], on_x_axis(std::slice::from_ref(&trace), &relative)[0].lb_plot("red", &relative).data);
+        assert_renders(&bounds_view(&[trace], &relative), &["Time (s)"], 6);
+    }
+    #[test]
+    fn grid_lines_along_the_chosen_axes() {
+        let traces  = vec![Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10\nFinal 12, Explored 500")];
+        let grid    = |grid| {
+            let options = ViewOptions { grid, ..ViewOptions::default() };
+            bounds_view(&traces, &options).grid().as_ref().map(|g| (g.nx, g.ny))
+        };
+        assert_eq!(None,         grid(GridLines::Off));
+        assert_eq!(Some((6, 2)), grid(GridLines::X));
+        assert_eq!(Some((2, 6)), grid(GridLines::Y));
+        assert_eq!(Some((6, 6)), grid(GridLines::Both));
+        let options = ViewOptions { grid: GridLines::Both, ..ViewOptions::default() };
+        assert!(fringe_view(&traces, &options).grid().is_some());
+    }
+    #[test]
+    fn crossings_plot_only_when_the_bounds_cross() {
+        let trace = Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10\nExplored 200, LB 35, UB 30, Fringe sz 10");
+        let options = ViewOptions { highlight_crossings: true, ..ViewOptions::default() };
+        assert!(trace.crossings_plot(&options).is_some());
+        assert!(Trace::from("Explored 100, LB 0, UB 40, Fringe sz 10").crossings_plot(&options).is_none());
+    }
 }
\ No newline at end of file
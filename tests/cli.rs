@@ -0,0 +1,162 @@
+//! End-to-end tests which run the plotter binary against the sample trace.
+
+use std::fs;
+
+use assert_cmd::Command;
+
+/// The sample trace shipped with the sources
+const SAMPLE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/example.trc");
+
+#[test]
+fn saves_the_graph_to_svg() {
+    let out = std::env::temp_dir().join("ddo-plotter-cli-test.svg");
+    let _   = fs::remove_file(&out);
+
+    Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("-o").arg(&out)
+        .assert()
+        .success();
+
+    assert!(fs::read_to_string(&out).unwrap().contains("<svg"));
+    let _ = fs::remove_file(&out);
+}
+#[test]
+fn draws_both_gaps_in_two_cells() {
+    let out = std::env::temp_dir().join("ddo-plotter-cli-test-gaps.svg");
+    let _   = fs::remove_file(&out);
+
+    Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("--gap-mode").arg("both")
+        .arg("-o").arg(&out)
+        .assert()
+        .success();
+
+    let svg = fs::read_to_string(&out).unwrap();
+    assert_eq!(2, svg.matches("<svg x=").count(), "the gaps are not in two cells:\n{}", svg);
+    let _ = fs::remove_file(&out);
+}
+#[test]
+fn names_the_output_after_the_trace() {
+    let dir = std::env::temp_dir().join("ddo-plotter-cli-test-name");
+    let _   = fs::remove_dir_all(&dir);
+
+    Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("-o").arg(dir.join("{name}.svg"))
+        .assert()
+        .success();
+
+    assert!(dir.join("example.svg").exists());
+    let _ = fs::remove_dir_all(&dir);
+}
+#[test]
+fn names_the_trace_with_name() {
+    let dir = std::env::temp_dir().join("ddo-plotter-cli-test-worker");
+    let _   = fs::remove_dir_all(&dir);
+
+    Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("--name").arg("w0")
+        .arg("-o").arg(dir.join("{name}.svg"))
+        .assert()
+        .success();
+
+    assert!(dir.join("w0.svg").exists());
+    let _ = fs::remove_dir_all(&dir);
+}
+#[test]
+fn rejects_a_name_placeholder_for_overlaid_traces() {
+    let out = std::env::temp_dir().join("{name}.svg");
+
+    Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE).arg(SAMPLE)
+        .arg("-o").arg(&out)
+        .assert()
+        .code(1);
+}
+#[test]
+fn prints_the_graph_as_text() {
+    let assert = Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("-d").arg("100,30")
+        .assert()
+        .success();
+    let text   = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+
+    assert!(text.contains("Explored Nodes"), "the axis label is missing from:\n{}", text);
+}
+#[test]
+fn prints_only_the_svg_with_svg_stdout() {
+    let assert = Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("--svg-stdout")
+        .assert()
+        .success();
+    let svg    = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+
+    assert!(svg.starts_with("<svg"), "the output is not pure svg:\n{}", svg);
+    assert!(svg.trim_end().ends_with("</svg>"), "the output is not pure svg:\n{}", svg);
+}
+#[test]
+fn warnings_go_to_stderr_and_the_plot_to_stdout() {
+    let log = std::env::temp_dir().join("ddo-plotter-cli-test-crossing.trc");
+    fs::write(&log, "Explored 100, LB 0, UB 40, Fringe sz 10\nExplored 200, LB 35, UB 30, Fringe sz 10\nFinal 30, Explored 300\n").unwrap();
+
+    let assert = Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(&log)
+        .arg("-d").arg("100,30")
+        .arg("--highlight-crossings")
+        .assert()
+        .success();
+    let output = assert.get_output();
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    let _      = fs::remove_file(&log);
+
+    assert!(stderr.contains("exceeds its upper bound"), "the warning is missing from stderr:\n{}", stderr);
+    assert!(!stdout.contains("exceeds its upper bound"), "the warning leaked to stdout:\n{}", stdout);
+    assert!(stdout.contains("Explored Nodes"), "the plot is missing from stdout:\n{}", stdout);
+}
+#[test]
+fn rejects_another_x_axis_for_the_gap_view() {
+    let assert = Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("--x-axis").arg("index")
+        .arg("--gap-mode").arg("absolute")
+        .assert()
+        .code(1);
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("--gap-mode"), "the conflicting view is not named in:\n{}", stderr);
+}
+#[test]
+fn rejects_an_unknown_ddo_version() {
+    let assert = Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("--ddo-version").arg("0.0.1")
+        .assert()
+        .code(1);
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("Unknown ddo version '0.0.1'"), "the version is not reported in:\n{}", stderr);
+    assert!(stderr.contains("latest"), "the known versions are not listed in:\n{}", stderr);
+}
+#[test]
+fn fails_when_the_graph_cannot_be_saved() {
+    // The parent of the output is a regular file: no directory can be created there
+    let file = std::env::temp_dir().join("ddo-plotter-cli-test-not-a-dir");
+    fs::write(&file, "").unwrap();
+    let out  = file.join("graph.svg");
+
+    let assert = Command::cargo_bin("plotter").unwrap()
+        .arg("-i").arg(SAMPLE)
+        .arg("-o").arg(&out)
+        .assert()
+        .code(2);
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+    let _      = fs::remove_file(&file);
+
+    assert!(stderr.contains(&out.display().to_string()), "the path is missing from:\n{}", stderr);
+}